@@ -0,0 +1,102 @@
+//! The `Address` is the typed response of the `convert-to-3wa` and `convert-to-coordinates`
+//! endpoints. Two addresses can be compared field by field with `Address::diff`, which is useful
+//! to re-verify stored addresses.
+
+use serde::Deserialize;
+
+use crate::coordinate::Coordinate;
+use crate::country_code::CountryCode;
+use crate::square::Square;
+
+/// A three word address together with its location, as returned by the conversion endpoints.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Address {
+    /// The country the square is in, `ZZ` if it is not within a country
+    pub country: CountryCode,
+    /// The square which is addressed
+    pub square: Square,
+    /// The nearest place to the square
+    pub nearest_place: String,
+    /// The coordinates of the center of the square
+    pub coordinates: Coordinate,
+    /// The three word address
+    pub words: String,
+    /// The language of the three word address
+    pub language: String,
+    /// The locale of the three word address, if one was requested
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// A link to the square on the what3words map
+    pub map: String,
+}
+
+/// A single field which differs between two `Address`es.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AddressChange {
+    /// The three word address changed
+    Words { old: String, new: String },
+    /// The square changed
+    Square { old: Square, new: Square },
+    /// The coordinates of the center of the square changed
+    Coordinates { old: Coordinate, new: Coordinate },
+    /// The nearest place changed
+    NearestPlace { old: String, new: String },
+    /// The country changed
+    Country { old: CountryCode, new: CountryCode },
+}
+
+impl Address {
+    /// Return all fields which changed from `self` to `other`. An empty list means both
+    /// addresses describe the same square with the same words.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let options = ConvertToCoordinatesOptions::default();
+    /// let stored = w3_client.convert_to_coordinates_typed("filled.count.soap", &options)?;
+    /// let current = w3_client.convert_to_coordinates_typed("filled.count.soap", &options)?;
+    /// for change in stored.diff(&current) {
+    ///     println!("{:?}", change);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff(&self, other: &Address) -> Vec<AddressChange> {
+        let mut changes = Vec::new();
+        if self.words != other.words {
+            changes.push(AddressChange::Words {
+                old: self.words.clone(),
+                new: other.words.clone(),
+            });
+        }
+        if self.square != other.square {
+            changes.push(AddressChange::Square {
+                old: self.square,
+                new: other.square,
+            });
+        }
+        if self.coordinates != other.coordinates {
+            changes.push(AddressChange::Coordinates {
+                old: self.coordinates,
+                new: other.coordinates,
+            });
+        }
+        if self.nearest_place != other.nearest_place {
+            changes.push(AddressChange::NearestPlace {
+                old: self.nearest_place.clone(),
+                new: other.nearest_place.clone(),
+            });
+        }
+        if self.country != other.country {
+            changes.push(AddressChange::Country {
+                old: self.country,
+                new: other.country,
+            });
+        }
+        changes
+    }
+}
@@ -0,0 +1,402 @@
+//! The `BatchResult` collects the results of a bulk call together with their inputs, so a
+//! pipeline can persist what succeeded and retry or report what failed, without one bad input
+//! aborting the others. A `BatchAnalysis` scans the inputs before the bulk call, for duplicates
+//! and the number of API calls the bulk call will make. A `Job` is a single conversion sent
+//! to the channels of `W3WClient::pipeline`.
+
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+use crate::coordinate::Coordinate;
+use crate::error::W3WError;
+use crate::three_word_address::ThreeWordAddress;
+use crate::W3WClient;
+
+/// The result of one input of a batch.
+#[derive(Debug)]
+pub struct BatchItem<I, T> {
+    /// The position of the input in the batch
+    pub index: usize,
+    /// The input as it was passed
+    pub input: I,
+    /// The typed response, or the error of this input
+    pub result: Result<T, W3WError>,
+}
+
+/// A conversion for `W3WClient::pipeline`, made with the `defaults` of the client.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Job {
+    /// Convert coordinates to a 3word address, like `W3WClient::convert_to_3wa_typed`
+    ConvertTo3WA(Coordinate),
+    /// Convert a 3word address to coordinates, like `W3WClient::convert_to_coordinates_typed`
+    ConvertToCoordinates(String),
+}
+
+/// The results of a batch, one `BatchItem` per input in the order of the inputs.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::*;
+/// # fn main() -> Result<(), what3words::W3WError> {
+/// # let w3_client = W3WClient::new("your_api_key");
+/// # let options = ConvertTo3WAOptions::default();
+/// # let points = vec![Coordinate { latitude: 51.520847, longitude: -0.195521 }];
+/// let results = w3_client.convert_to_3wa_bulk(&points, &options, 8);
+/// let batch = BatchResult::new(points, results);
+/// println!("{} of {} converted", batch.succeeded(), batch.len());
+/// for (item, error) in batch.failures() {
+///     eprintln!("{} {:?}: {}", item.index, item.input, error);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct BatchResult<I, T> {
+    /// The result of every input
+    pub items: Vec<BatchItem<I, T>>,
+}
+
+impl<I, T> BatchResult<I, T> {
+    /// Pair every input with its result, like the inputs and the results of
+    /// `W3WClient::convert_to_3wa_bulk`. Inputs without a result are left out, as are results
+    /// without an input.
+    pub fn new(
+        inputs: impl IntoIterator<Item = I>,
+        results: impl IntoIterator<Item = Result<T, W3WError>>,
+    ) -> Self {
+        BatchResult {
+            items: inputs
+                .into_iter()
+                .zip(results)
+                .enumerate()
+                .map(|(index, (input, result))| BatchItem {
+                    index,
+                    input,
+                    result,
+                })
+                .collect(),
+        }
+    }
+
+    /// Return the number of inputs.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Return whether the batch has no inputs.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Return the number of inputs which succeeded.
+    pub fn succeeded(&self) -> usize {
+        self.items.iter().filter(|item| item.result.is_ok()).count()
+    }
+
+    /// Return the number of inputs which failed.
+    pub fn failed(&self) -> usize {
+        self.len() - self.succeeded()
+    }
+
+    /// Return whether every input succeeded.
+    pub fn is_complete(&self) -> bool {
+        self.items.iter().all(|item| item.result.is_ok())
+    }
+
+    /// Try the inputs which failed again with `f`, one after another, after waiting `delay`,
+    /// and replace their results with the new ones. Inputs which succeeded are kept as they
+    /// are. `f` may call another client, e.g. one with a different API key. Returns the number
+    /// of inputs which succeeded this time.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::time::Duration;
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let other_client = W3WClient::new("your_other_api_key");
+    /// # let options = ConvertTo3WAOptions::default();
+    /// # let points = vec![Coordinate { latitude: 51.520847, longitude: -0.195521 }];
+    /// # let mut batch = BatchResult::new(points.clone(), w3_client.convert_to_3wa_bulk(&points, &options, 8));
+    /// let recovered = batch.retry_failed(Duration::from_secs(60), |coordinate| {
+    ///     other_client.convert_to_3wa_typed(*coordinate, &options)
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn retry_failed(
+        &mut self,
+        delay: Duration,
+        mut f: impl FnMut(&I) -> Result<T, W3WError>,
+    ) -> usize {
+        if self.is_complete() {
+            return 0;
+        }
+        thread::sleep(delay);
+        let mut recovered = 0;
+        for item in self.items.iter_mut().filter(|item| item.result.is_err()) {
+            item.result = f(&item.input);
+            if item.result.is_ok() {
+                recovered += 1;
+            }
+        }
+        recovered
+    }
+
+    /// Return the inputs which succeeded, each with its response.
+    pub fn successes(&self) -> impl Iterator<Item = (&BatchItem<I, T>, &T)> {
+        self.items
+            .iter()
+            .filter_map(|item| item.result.as_ref().ok().map(|response| (item, response)))
+    }
+
+    /// Return the inputs which failed, each with its error.
+    pub fn failures(&self) -> impl Iterator<Item = (&BatchItem<I, T>, &W3WError)> {
+        self.items
+            .iter()
+            .filter_map(|item| item.result.as_ref().err().map(|error| (item, error)))
+    }
+}
+
+/// The inputs of a bulk call as the client would send them, to check a dataset and its quota
+/// before the job runs.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::*;
+/// # fn main() -> Result<(), what3words::W3WError> {
+/// # let w3_client = W3WClient::new("your_api_key");
+/// # let three_words = vec!["filled.count.soap", "index.home.raft"];
+/// let analysis = BatchAnalysis::of_words(&w3_client, &three_words);
+/// println!(
+///     "{} addresses, {} unique, {} malformed, about {} API calls",
+///     analysis.total, analysis.unique, analysis.malformed, analysis.estimated_calls
+/// );
+/// for (words, count) in analysis.duplicates.iter().take(10) {
+///     println!("{} appears {} times", words, count);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchAnalysis {
+    /// The number of inputs
+    pub total: usize,
+    /// The number of distinct inputs which are sent, after normalization
+    pub unique: usize,
+    /// The number of inputs which are rejected before a request is sent, e.g. coordinates out
+    /// of range
+    pub rejected: usize,
+    /// The number of 3word addresses which are not of the form `word.word.word`. They are sent,
+    /// but the API cannot convert them.
+    pub malformed: usize,
+    /// The inputs which occur more than once with their count, most frequent first
+    pub duplicates: Vec<(String, usize)>,
+    /// The number of API calls the bulk call is expected to make. Without an `address_cache`
+    /// every input which is sent is a call; with one, every distinct input which can be
+    /// converted is a call. Duplicates which are in flight at the same time may both be sent,
+    /// so more calls are possible with a high concurrency.
+    pub estimated_calls: usize,
+}
+
+impl BatchAnalysis {
+    /// Analyze the coordinates of a `W3WClient::convert_to_3wa_bulk` call, checked, normalized
+    /// and rounded like `client` sends them.
+    pub fn of_coordinates(client: &W3WClient, coordinates: &[Coordinate]) -> Self {
+        BatchAnalysis::new(
+            client,
+            coordinates.iter().map(|coordinate| {
+                client
+                    .outgoing_coordinate(coordinate)
+                    .ok()
+                    .map(|coordinate| (coordinate.to_string(), true))
+            }),
+        )
+    }
+
+    /// Analyze the 3word addresses of a `W3WClient::convert_to_coordinates_bulk` call, which
+    /// are normalized like `ThreeWordAddress::normalize` and compared without case.
+    pub fn of_words(
+        client: &W3WClient,
+        three_words: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Self {
+        BatchAnalysis::new(
+            client,
+            three_words.into_iter().map(|words| {
+                let normalized = ThreeWordAddress::normalize(words.as_ref()).to_lowercase();
+                let well_formed = normalized.parse::<ThreeWordAddress>().is_ok();
+                Some((normalized, well_formed))
+            }),
+        )
+    }
+
+    /// Count the inputs, given as the key they are sent with and whether a result can be
+    /// cached, or `None` for an input which is rejected before it is sent.
+    fn new(client: &W3WClient, inputs: impl Iterator<Item = Option<(String, bool)>>) -> Self {
+        let mut total = 0;
+        let mut rejected = 0;
+        let mut counts: HashMap<String, (usize, bool)> = HashMap::new();
+        for input in inputs {
+            total += 1;
+            match input {
+                Some((key, cacheable)) => counts.entry(key).or_insert((0, cacheable)).0 += 1,
+                None => rejected += 1,
+            }
+        }
+        let malformed = counts
+            .values()
+            .filter(|(_, cacheable)| !cacheable)
+            .map(|(count, _)| count)
+            .sum();
+        let estimated_calls = if client.address_cache.is_some() {
+            counts
+                .values()
+                .map(|&(count, cacheable)| if cacheable { 1 } else { count })
+                .sum()
+        } else {
+            total - rejected
+        };
+        let unique = counts.len();
+        let mut duplicates: Vec<(String, usize)> = counts
+            .into_iter()
+            .filter(|(_, (count, _))| *count > 1)
+            .map(|(key, (count, _))| (key, count))
+            .collect();
+        duplicates.sort_by(|(a_key, a_count), (b_key, b_count)| {
+            b_count.cmp(a_count).then_with(|| a_key.cmp(b_key))
+        });
+        BatchAnalysis {
+            total,
+            unique,
+            rejected,
+            malformed,
+            duplicates,
+            estimated_calls,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{BatchAnalysis, BatchResult};
+    use crate::error::W3WError;
+    use crate::{AddressCache, Coordinate, W3WClient};
+
+    #[test]
+    fn test_batch_result_counts_and_splits_the_results() {
+        let batch = BatchResult::new(
+            ["filled.count.soap", "not an address", "index.home.raft"],
+            vec![
+                Ok(1),
+                Err(W3WError::Validation("not an address".to_string())),
+                Ok(3),
+            ],
+        );
+        assert_eq!(batch.len(), 3);
+        assert_eq!(batch.succeeded(), 2);
+        assert_eq!(batch.failed(), 1);
+        assert!(!batch.is_complete());
+
+        let successes: Vec<(usize, i32)> = batch
+            .successes()
+            .map(|(item, response)| (item.index, *response))
+            .collect();
+        assert_eq!(successes, vec![(0, 1), (2, 3)]);
+        let failures: Vec<(usize, &str)> = batch
+            .failures()
+            .map(|(item, _)| (item.index, item.input))
+            .collect();
+        assert_eq!(failures, vec![(1, "not an address")]);
+    }
+
+    #[test]
+    fn test_retry_failed_only_retries_the_failures() {
+        let mut batch = BatchResult::new(
+            ["a", "b", "c"],
+            vec![
+                Ok(1),
+                Err(W3WError::Validation("b".to_string())),
+                Err(W3WError::Validation("c".to_string())),
+            ],
+        );
+        let mut retried = Vec::new();
+        let recovered = batch.retry_failed(Duration::ZERO, |input| {
+            retried.push(*input);
+            match *input {
+                "b" => Ok(2),
+                _ => Err(W3WError::Validation(input.to_string())),
+            }
+        });
+        assert_eq!(recovered, 1);
+        assert_eq!(retried, vec!["b", "c"]);
+        assert_eq!(batch.succeeded(), 2);
+        assert_eq!(batch.items[1].result.as_ref().unwrap(), &2);
+        assert_eq!(batch.failures().next().unwrap().0.index, 2);
+
+        assert_eq!(batch.retry_failed(Duration::ZERO, |_| Ok(3)), 1);
+        assert!(batch.is_complete());
+        assert_eq!(batch.retry_failed(Duration::ZERO, |_| unreachable!()), 0);
+    }
+
+    #[test]
+    fn test_batch_analysis_counts_duplicate_words_and_calls() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        let three_words = [
+            "filled.count.soap",
+            "///Filled.Count.Soap",
+            "index.home.raft",
+            "filled.count",
+            "filled.count",
+            "https://w3w.co/filled.count.soap",
+        ];
+        let analysis = BatchAnalysis::of_words(&w3_client, three_words);
+        assert_eq!(analysis.total, 6);
+        assert_eq!(analysis.unique, 3);
+        assert_eq!(analysis.rejected, 0);
+        assert_eq!(analysis.malformed, 2);
+        assert_eq!(
+            analysis.duplicates,
+            [
+                (String::from("filled.count.soap"), 3),
+                (String::from("filled.count"), 2)
+            ]
+        );
+        assert_eq!(analysis.estimated_calls, 6);
+
+        w3_client.address_cache = Some(AddressCache::new(10));
+        let analysis = BatchAnalysis::of_words(&w3_client, three_words);
+        assert_eq!(analysis.estimated_calls, 4);
+    }
+
+    #[test]
+    fn test_batch_analysis_checks_coordinates_like_the_client() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.coordinate_precision = Some(4);
+        w3_client.address_cache = Some(AddressCache::new(10));
+        let coordinates = [
+            Coordinate::from((51.520812, -0.195521)),
+            Coordinate::from((51.520834, -0.195524)),
+            Coordinate::from((f64::NAN, 4.0)),
+            Coordinate::from((51.5, 359.9)),
+        ];
+        let analysis = BatchAnalysis::of_coordinates(&w3_client, &coordinates);
+        assert_eq!(analysis.total, 4);
+        assert_eq!(analysis.unique, 1);
+        assert_eq!(analysis.rejected, 2);
+        assert_eq!(analysis.malformed, 0);
+        assert_eq!(analysis.duplicates, [(String::from("51.5208,-0.1955"), 2)]);
+        assert_eq!(analysis.estimated_calls, 1);
+
+        w3_client.normalize_coordinates = true;
+        let analysis = BatchAnalysis::of_coordinates(&w3_client, &coordinates);
+        assert_eq!(analysis.unique, 2);
+        assert_eq!(analysis.rejected, 1);
+        assert_eq!(analysis.estimated_calls, 2);
+    }
+}
@@ -1,26 +1,457 @@
 //! A `BoundingBox` is a rectangle defined by 2 coordinates: the southwestern and northeastern coordinates.
 //! This BoundingBox can be used to pass as an option to certain what3words calls.
+//!
+//! A box which crosses the antimeridian has a northeastern longitude beyond 180, e.g. from 178.2
+//! to 195.4, as the what3words API documents. `BoundingBox::new` brings other longitudes into
+//! that form.
 
+use std::fmt;
+
+use crate::circle::Circle;
 use crate::coordinate::Coordinate;
+use crate::error::W3WError;
+use crate::geo_utils::{
+    haversine_distance, meters_per_degree_latitude, meters_per_degree_longitude, wrap_longitude,
+};
+use crate::polygon::Polygon;
+
+/// The largest distance from corner to corner, in meters, of the bounding box of a grid section.
+pub(crate) const MAX_GRID_SECTION_DIAGONAL_M: f64 = 4000.0;
+/// The largest side, in meters, of the tiles of `BoundingBox::grid_section_tiles`. This keeps
+/// the diagonal of a tile well below `MAX_GRID_SECTION_DIAGONAL_M`, also where the width of a
+/// tile at its edge differs from the width at its center.
+const GRID_SECTION_TILE_SIDE_M: f64 = 2500.0;
+/// The largest number of tiles of `BoundingBox::grid_section_tiles`, one API call each. 1000
+/// tiles cover about 6000 km², more than the largest cities.
+pub(crate) const MAX_GRID_SECTION_TILES: usize = 1000;
 
 /// A rectangle which is defined by the coordinate of the southwestern point and the coordinate
 /// of the northeastern point.
-#[derive(Debug)]
-pub struct BoundingBox<'a> {
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundingBox {
     /// Coordinates of the southwestern point
-    pub south_west: &'a Coordinate,
+    pub south_west: Coordinate,
     /// Coordinates of the northeastern point
-    pub north_east: &'a Coordinate,
+    pub north_east: Coordinate,
+}
+
+impl BoundingBox {
+    /// Creates a bounding box after checking its corners. The latitudes must be within -90 and
+    /// 90, with the southwestern corner south of the northeastern corner. The longitudes are
+    /// wrapped, and when the box crosses the antimeridian the northeastern longitude is put
+    /// beyond 180, so `170,-170` becomes `170,190`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// let fiji = BoundingBox::new((-21.0, 177.0), (-12.0, -178.0))?;
+    /// assert_eq!(fiji.north_east.longitude, 182.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(
+        south_west: impl Into<Coordinate>,
+        north_east: impl Into<Coordinate>,
+    ) -> Result<BoundingBox, W3WError> {
+        let (south_west, north_east) = (south_west.into(), north_east.into());
+        let bounding_box = BoundingBox {
+            south_west,
+            north_east,
+        };
+        bounding_box.validate()?;
+        let west = match south_west.longitude {
+            west if (-180.0..=180.0).contains(&west) => west,
+            west => wrap_longitude(west),
+        };
+        let east = match north_east.longitude {
+            _ if north_east.longitude - south_west.longitude >= 360.0 => west + 360.0,
+            east if (west..west + 360.0).contains(&east) => east,
+            east => match wrap_longitude(east) {
+                east if east < west => east + 360.0,
+                east => east,
+            },
+        };
+        Ok(BoundingBox {
+            south_west: Coordinate {
+                latitude: south_west.latitude,
+                longitude: west,
+            },
+            north_east: Coordinate {
+                latitude: north_east.latitude,
+                longitude: east,
+            },
+        })
+    }
+
+    /// Creates a box of `width_m` by `height_m` meters around `center`, e.g. the 500 m box
+    /// around a point of a grid section. Degrees of longitude shrink towards the poles, so the
+    /// width is converted at the latitude of the center.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let address = w3_client.convert_to_coordinates_typed("filled.count.soap", &ConvertToCoordinatesOptions::default())?;
+    /// let bounding_box = BoundingBox::from_center(address.coordinates, 500.0, 500.0);
+    /// let grid = w3_client.grid_section_typed(&bounding_box, &GridSectionOptions::default())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_center(center: impl Into<Coordinate>, width_m: f64, height_m: f64) -> BoundingBox {
+        let center = center.into();
+        let d_lat = height_m / 2.0 / meters_per_degree_latitude();
+        let d_lng = width_m / 2.0 / meters_per_degree_longitude(center.latitude);
+        BoundingBox {
+            south_west: Coordinate {
+                latitude: (center.latitude - d_lat).max(-90.0),
+                longitude: center.longitude - d_lng,
+            },
+            north_east: Coordinate {
+                latitude: (center.latitude + d_lat).min(90.0),
+                longitude: center.longitude + d_lng,
+            },
+        }
+    }
+
+    /// Creates the smallest box around the circle.
+    pub fn from_circle(circle: &Circle) -> BoundingBox {
+        let diameter_m = circle.radius_m() * 2.0;
+        BoundingBox::from_center(circle.centerpoint, diameter_m, diameter_m)
+    }
+
+    /// Return whether the coordinate lies within the box, edges included. A box crosses the
+    /// antimeridian when its northeastern longitude is beyond 180 or smaller than its
+    /// southwestern longitude.
+    pub fn contains(&self, coordinate: &Coordinate) -> bool {
+        let (west, east) = (self.south_west.longitude, self.north_east.longitude);
+        let within_longitude = if west <= east {
+            (west..=east).contains(&coordinate.longitude)
+                || (west..=east).contains(&(coordinate.longitude + 360.0))
+        } else {
+            coordinate.longitude >= west || coordinate.longitude <= east
+        };
+        within_longitude
+            && (self.south_west.latitude..=self.north_east.latitude).contains(&coordinate.latitude)
+    }
+
+    /// Return the box as a polygon of its four corners, counter-clockwise from the
+    /// southwestern corner.
+    pub fn to_polygon(&self) -> Polygon {
+        Polygon {
+            coordinates: vec![
+                self.south_west,
+                Coordinate {
+                    latitude: self.south_west.latitude,
+                    longitude: self.north_east.longitude,
+                },
+                self.north_east,
+                Coordinate {
+                    latitude: self.north_east.latitude,
+                    longitude: self.south_west.longitude,
+                },
+            ],
+        }
+    }
+
+    /// Return the box as a Well-Known Text polygon of its four corners, e.g.
+    /// `POLYGON((4 51, 5 51, 5 52, 4 52, 4 51))`.
+    pub fn to_wkt(&self) -> String {
+        self.to_polygon().to_wkt()
+    }
+
+    /// Return the approximate width (east-west) and height (north-south) of the bounding box on
+    /// the ground in meters. The width is measured at the latitude of the center of the box.
+    pub fn approx_size_m(&self) -> (f64, f64) {
+        (self.width_m(), self.height_m())
+    }
+
+    /// Return the approximate width (east-west) of the box on the ground in meters, measured at
+    /// the latitude of its center. The width is measured eastward from the southwestern corner,
+    /// so a box which crosses the antimeridian has the same width in both of its forms.
+    pub fn width_m(&self) -> f64 {
+        self.longitude_span() * meters_per_degree_longitude(self.center_latitude())
+    }
+
+    /// Return the height (north-south) of the box on the ground in meters.
+    pub fn height_m(&self) -> f64 {
+        let d_lat = self.north_east.latitude - self.south_west.latitude;
+        d_lat.abs() * meters_per_degree_latitude()
+    }
+
+    /// Return the approximate area of the box on the ground in square meters.
+    pub fn area_m2(&self) -> f64 {
+        self.width_m() * self.height_m()
+    }
+
+    /// Return the box grown by `meters` on every side, e.g. to fetch a margin around a clip. A
+    /// negative distance shrinks the box, and shrinking a side by half of it or more collapses
+    /// that side to the center of the box, so the corners never swap. Latitudes are clamped to
+    /// the poles. A box which crosses the antimeridian is returned with its northeastern
+    /// longitude beyond 180.
+    pub fn expand_meters(&self, meters: f64) -> BoundingBox {
+        let d_lat = meters / meters_per_degree_latitude();
+        let d_lng = meters / meters_per_degree_longitude(self.center_latitude());
+        let (south, north) = grow(self.south_west.latitude, self.north_east.latitude, d_lat);
+        let (west, east) = grow(self.south_west.longitude, self.east_longitude(), d_lng);
+        BoundingBox {
+            south_west: Coordinate {
+                latitude: south.max(-90.0),
+                longitude: west,
+            },
+            north_east: Coordinate {
+                latitude: north.min(90.0),
+                longitude: east,
+            },
+        }
+    }
+
+    /// Return the box which both boxes cover, or `None` if they do not overlap. Boxes which
+    /// only touch have an intersection without area. Both boxes must use the same longitudes
+    /// for the antimeridian, either both wrapped or both beyond 180.
+    pub fn intersection(&self, other: &BoundingBox) -> Option<BoundingBox> {
+        let south_west = Coordinate {
+            latitude: self.south_west.latitude.max(other.south_west.latitude),
+            longitude: self.south_west.longitude.max(other.south_west.longitude),
+        };
+        let north_east = Coordinate {
+            latitude: self.north_east.latitude.min(other.north_east.latitude),
+            longitude: self.north_east.longitude.min(other.north_east.longitude),
+        };
+        (south_west.latitude <= north_east.latitude && south_west.longitude <= north_east.longitude)
+            .then_some(BoundingBox {
+                south_west,
+                north_east,
+            })
+    }
+
+    /// Return the latitude halfway between the southern and northern edge.
+    fn center_latitude(&self) -> f64 {
+        (self.south_west.latitude + self.north_east.latitude) / 2.0
+    }
+
+    /// Return the longitude of the eastern edge, at or east of the western edge. It is beyond
+    /// 180 when the box crosses the antimeridian with a northeastern longitude smaller than its
+    /// southwestern longitude.
+    fn east_longitude(&self) -> f64 {
+        let (west, east) = (self.south_west.longitude, self.north_east.longitude);
+        if east >= west {
+            east
+        } else {
+            west + (east - west).rem_euclid(360.0)
+        }
+    }
+
+    /// Return the number of degrees from the western edge eastward to the eastern edge.
+    fn longitude_span(&self) -> f64 {
+        self.east_longitude() - self.south_west.longitude
+    }
+
+    /// Return the great-circle distance in meters from the southwestern to the northeastern
+    /// corner.
+    pub fn diagonal_m(&self) -> f64 {
+        haversine_distance(&self.south_west, &self.north_east)
+    }
+
+    /// Split the box into equally sized tiles which are each small enough for a grid section,
+    /// ordered from south to north and then from west to east. A box which is small enough is
+    /// returned as the only tile. A box which crosses the antimeridian is tiled eastward, with
+    /// the longitudes of the tiles beyond 180 where they cross it. A box which needs more than
+    /// `MAX_GRID_SECTION_TILES` tiles is reported as a validation error with the number of
+    /// tiles, before any tile is made.
+    pub(crate) fn grid_section_tiles(&self) -> Result<Vec<BoundingBox>, W3WError> {
+        self.validate()?;
+        let columns = (self.width_m() / GRID_SECTION_TILE_SIDE_M).ceil().max(1.0);
+        let rows = (self.height_m() / GRID_SECTION_TILE_SIDE_M).ceil().max(1.0);
+        if columns * rows > MAX_GRID_SECTION_TILES as f64 {
+            return Err(W3WError::Validation(format!(
+                "the bounding box '{}' needs {} grid section tiles, but at most {} are fetched",
+                self,
+                columns * rows,
+                MAX_GRID_SECTION_TILES
+            )));
+        }
+        let (columns, rows) = (columns as usize, rows as usize);
+        let east = self.east_longitude();
+        let d_lat = (self.north_east.latitude - self.south_west.latitude) / rows as f64;
+        let d_lng = (east - self.south_west.longitude) / columns as f64;
+        let latitude = |row: usize| match row {
+            row if row == rows => self.north_east.latitude,
+            row => self.south_west.latitude + row as f64 * d_lat,
+        };
+        let longitude = |column: usize| match column {
+            column if column == columns => east,
+            column => self.south_west.longitude + column as f64 * d_lng,
+        };
+        Ok((0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| BoundingBox {
+                    south_west: Coordinate {
+                        latitude: latitude(row),
+                        longitude: longitude(column),
+                    },
+                    north_east: Coordinate {
+                        latitude: latitude(row + 1),
+                        longitude: longitude(column + 1),
+                    },
+                })
+            })
+            .collect())
+    }
+
+    /// Check that the latitudes of the corners are valid and in order, which the what3words
+    /// API would otherwise report as an error.
+    pub(crate) fn validate(&self) -> Result<(), W3WError> {
+        for corner in [&self.south_west, &self.north_east] {
+            if !corner.longitude.is_finite() || !(-90.0..=90.0).contains(&corner.latitude) {
+                return Err(W3WError::Validation(format!(
+                    "invalid corner '{}' of bounding box '{}'",
+                    corner, self
+                )));
+            }
+        }
+        if self.south_west.latitude > self.north_east.latitude {
+            return Err(W3WError::Validation(format!(
+                "the southwestern corner of bounding box '{}' is north of its northeastern \
+                 corner, are the corners swapped?",
+                self
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check that the box is small enough for a grid section, which the what3words API limits
+    /// to 4 km from corner to corner. The width is checked as well, as the diagonal of a box
+    /// which spans most of the globe from west to east can be short.
+    pub(crate) fn validate_grid_section(&self) -> Result<(), W3WError> {
+        let diagonal = self.diagonal_m();
+        if diagonal <= MAX_GRID_SECTION_DIAGONAL_M && self.width_m() <= MAX_GRID_SECTION_DIAGONAL_M
+        {
+            return Ok(());
+        }
+        let (width, height) = self.approx_size_m();
+        Err(W3WError::Validation(format!(
+            "the bounding box '{}' is {:.2} km from corner to corner ({:.2} km wide and {:.2} km \
+             high), but a grid section is limited to {} km",
+            self,
+            diagonal / 1000.0,
+            width / 1000.0,
+            height / 1000.0,
+            MAX_GRID_SECTION_DIAGONAL_M / 1000.0
+        )))
+    }
 }
 
-impl BoundingBox<'_> {
-    /// Return the BoundingBox as a String in the form
+/// Move the edges `low` and `high` apart by `delta` each, or to the middle between them when a
+/// negative `delta` would swap them.
+fn grow(low: f64, high: f64, delta: f64) -> (f64, f64) {
+    if high - low + 2.0 * delta >= 0.0 {
+        (low - delta, high + delta)
+    } else {
+        let middle = (low + high) / 2.0;
+        (middle, middle)
+    }
+}
+
+impl fmt::Display for BoundingBox {
+    /// Format the BoundingBox in the form
     /// `"<south_west.latitude>,<south_west.longitude>,<north_east.latitude>,<north_east.longitude>"`
-    pub fn to_string(&self) -> String {
-        format!(
-            "{},{}",
-            self.south_west.to_string(),
-            self.north_east.to_string()
-        )
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.south_west, self.north_east)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BoundingBox;
+    use crate::coordinate::Coordinate;
+    use crate::error::W3WError;
+
+    #[test]
+    fn test_grid_section_tiles_fit_the_limit() {
+        let london = BoundingBox {
+            south_west: Coordinate {
+                latitude: 51.45,
+                longitude: -0.25,
+            },
+            north_east: Coordinate {
+                latitude: 51.55,
+                longitude: -0.05,
+            },
+        };
+        let tiles = london.grid_section_tiles().unwrap();
+        assert_eq!(tiles.len(), 5 * 6);
+        assert!(tiles
+            .iter()
+            .all(|tile| tile.validate_grid_section().is_ok()));
+        assert_eq!(tiles[0].south_west, london.south_west);
+        assert_eq!(tiles[tiles.len() - 1].north_east, london.north_east);
+        assert_eq!(tiles[0].north_east.longitude, tiles[1].south_west.longitude);
+
+        let small = tiles[0].clone();
+        assert_eq!(small.grid_section_tiles().unwrap(), [small]);
+    }
+
+    #[test]
+    fn test_grid_section_tiles_cross_the_antimeridian_eastward() {
+        let wrapped = BoundingBox {
+            south_west: Coordinate {
+                latitude: 0.0,
+                longitude: 179.99,
+            },
+            north_east: Coordinate {
+                latitude: 0.005,
+                longitude: -179.99,
+            },
+        };
+        assert!(wrapped.validate_grid_section().is_ok());
+        let tiles = wrapped.grid_section_tiles().unwrap();
+        assert_eq!(tiles.len(), 1);
+        assert_eq!(tiles[0].south_west.longitude, 179.99);
+        assert!((tiles[0].north_east.longitude - 180.01).abs() < 1e-9);
+
+        let band = BoundingBox {
+            north_east: Coordinate {
+                latitude: 0.005,
+                longitude: 179.98,
+            },
+            ..wrapped
+        };
+        assert!(band.validate_grid_section().is_err());
+    }
+
+    #[test]
+    fn test_grid_section_tiles_are_capped() {
+        let world = BoundingBox {
+            south_west: Coordinate {
+                latitude: -90.0,
+                longitude: -180.0,
+            },
+            north_east: Coordinate {
+                latitude: 90.0,
+                longitude: 180.0,
+            },
+        };
+        assert!(matches!(
+            world.grid_section_tiles(),
+            Err(W3WError::Validation(_))
+        ));
+        let degree = BoundingBox {
+            south_west: Coordinate {
+                latitude: 0.0,
+                longitude: 0.0,
+            },
+            north_east: Coordinate {
+                latitude: 1.0,
+                longitude: 1.0,
+            },
+        };
+        assert!(matches!(
+            degree.grid_section_tiles(),
+            Err(W3WError::Validation(message)) if message.contains("needs 2025 grid section tiles")
+        ));
     }
 }
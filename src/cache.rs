@@ -0,0 +1,241 @@
+//! The `AutosuggestCache` keeps the suggestions of recent autosuggest calls, so the repeated
+//! input of a search box, like going back from `filled.` to `filled`, does not hit the API
+//! again. It is used by the typed autosuggest calls when set on the client.
+//!
+//! The `AddressCache` keeps the addresses of recent `convert_to_3wa_typed` and
+//! `convert_to_coordinates_typed` calls. Any coordinate within the square of a cached address
+//! is answered from the cache, which saves most calls when walking the neighbors of a square,
+//! and so is the three word address itself.
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+
+use crate::address::Address;
+use crate::coordinate::Coordinate;
+use crate::square::Square;
+use crate::suggestion::Suggestion;
+
+/// A least-recently-used cache of autosuggest results, keyed on the normalized input and all
+/// query parameters of the call.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::*;
+/// # fn main() -> Result<(), what3words::W3WError> {
+/// let mut w3_client = W3WClient::new("<your API key>");
+/// w3_client.autosuggest_cache = Some(AutosuggestCache::new(100));
+/// # Ok(())
+/// # }
+/// ```
+pub struct AutosuggestCache {
+    entries: Lru<Vec<Suggestion>>,
+}
+
+impl AutosuggestCache {
+    /// Creates a cache which holds the results of at most `capacity` calls.
+    pub fn new(capacity: usize) -> Self {
+        AutosuggestCache {
+            entries: Lru::new(capacity),
+        }
+    }
+
+    /// Return the number of cached results.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all cached results.
+    pub fn clear(&self) {
+        self.entries.clear();
+    }
+
+    /// Return the cached suggestions for `key`, which become the most recently used.
+    pub(crate) fn get(&self, key: &str) -> Option<Vec<Suggestion>> {
+        self.entries.find(|cached, _| cached == key)
+    }
+
+    /// Cache the suggestions for `key`, evicting the least recently used result when full.
+    pub(crate) fn insert(&self, key: String, suggestions: Vec<Suggestion>) {
+        self.entries
+            .insert(key.clone(), suggestions, |cached, _| *cached == key);
+    }
+}
+
+impl fmt::Debug for AutosuggestCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AutosuggestCache")
+            .field("capacity", &self.entries.capacity)
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// A least-recently-used cache of the addresses of `convert_to_3wa_typed` and
+/// `convert_to_coordinates_typed`, keyed on the `convert_to_3wa` options which return them.
+/// Addresses are looked up by the square which contains the coordinate, or by their words.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::*;
+/// # fn main() -> Result<(), what3words::W3WError> {
+/// let mut w3_client = W3WClient::new("<your API key>");
+/// w3_client.address_cache = Some(AddressCache::new(1000));
+/// # Ok(())
+/// # }
+/// ```
+pub struct AddressCache {
+    entries: Lru<Address>,
+}
+
+impl AddressCache {
+    /// Creates a cache which holds at most `capacity` addresses.
+    pub fn new(capacity: usize) -> Self {
+        AddressCache {
+            entries: Lru::new(capacity),
+        }
+    }
+
+    /// Return the number of cached addresses.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Return whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all cached addresses.
+    pub fn clear(&self) {
+        self.entries.clear();
+    }
+
+    /// Return the cached address, for the options `key`, of the square which contains the
+    /// coordinate. It becomes the most recently used.
+    pub(crate) fn get(&self, key: &str, coordinate: &Coordinate) -> Option<Address> {
+        self.entries
+            .find(|cached, address| cached == key && address.square.contains(coordinate))
+    }
+
+    /// Return the square of any cached address which contains the coordinate, whatever its
+    /// options key, as the squares do not depend on the language. The address becomes the
+    /// most recently used.
+    pub(crate) fn get_square(&self, coordinate: &Coordinate) -> Option<Square> {
+        self.entries
+            .find(|_, address| address.square.contains(coordinate))
+            .map(|address| address.square)
+    }
+
+    /// Return the cached address with the given words and locale, whatever its options key.
+    /// It becomes the most recently used.
+    pub(crate) fn get_by_words(&self, words: &str, locale: Option<&str>) -> Option<Address> {
+        self.entries
+            .find(|_, address| address.words == words && address.locale.as_deref() == locale)
+    }
+
+    /// Cache the address for the options `key`, evicting the least recently used address when
+    /// full.
+    pub(crate) fn insert(&self, key: String, address: Address) {
+        let square = address.square;
+        self.entries
+            .insert(key.clone(), address, |cached, address| {
+                *cached == key && address.square == square
+            });
+    }
+}
+
+impl fmt::Debug for AddressCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AddressCache")
+            .field("capacity", &self.entries.capacity)
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// The least-recently-used list behind the caches, most recently used last.
+struct Lru<V> {
+    capacity: usize,
+    entries: Mutex<VecDeque<(String, V)>>,
+}
+
+impl<V: Clone> Lru<V> {
+    fn new(capacity: usize) -> Self {
+        Lru {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries().len()
+    }
+
+    fn clear(&self) {
+        self.entries().clear();
+    }
+
+    /// Return the first value for which `matches` holds, which becomes the most recently used.
+    fn find(&self, matches: impl Fn(&str, &V) -> bool) -> Option<V> {
+        let mut entries = self.entries();
+        let position = entries
+            .iter()
+            .position(|(key, value)| matches(key, value))?;
+        let entry = entries.remove(position)?;
+        let value = entry.1.clone();
+        entries.push_back(entry);
+        Some(value)
+    }
+
+    /// Add the value for `key`, dropping the earlier values it `replaces`, and evict the least
+    /// recently used value when full.
+    fn insert(&self, key: String, value: V, replaces: impl Fn(&str, &V) -> bool) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries();
+        entries.retain(|(cached, value)| !replaces(cached, value));
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back((key, value));
+    }
+
+    /// Lock the entries. A panic while the lock was held cannot leave them half-updated, so a
+    /// poisoned lock is used as is.
+    fn entries(&self) -> MutexGuard<'_, VecDeque<(String, V)>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AutosuggestCache;
+
+    #[test]
+    fn test_least_recently_used_result_is_evicted() {
+        let cache = AutosuggestCache::new(2);
+        cache.insert(String::from("input=filled"), Vec::new());
+        cache.insert(String::from("input=filled.count"), Vec::new());
+        assert!(cache.get("input=filled").is_some());
+
+        cache.insert(String::from("input=filled.count.so"), Vec::new());
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("input=filled.count").is_none());
+        assert!(cache.get("input=filled").is_some());
+        assert!(cache.get("input=filled.count.so").is_some());
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
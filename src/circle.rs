@@ -1,20 +1,118 @@
 //! The `Circle` can be used to define a circle which can be used in some What3Words API calls.
-//! A circle consist of a centerpoint coordinate and a radius in kilometers.
+//! A circle consist of a centerpoint coordinate and a radius in kilometers. The `from_meters`,
+//! `from_kilometers` and `from_miles` constructors state the unit of the radius explicitly.
+
+use std::fmt;
 
 use crate::coordinate::Coordinate;
+use crate::geo_utils::{destination, haversine_distance};
+use crate::polygon::{Polygon, MAX_POLYGON_PAIRS};
+
+/// The number of meters in a kilometer.
+const METERS_PER_KILOMETER: f64 = 1000.0;
+/// The number of meters in an international mile.
+const METERS_PER_MILE: f64 = 1609.344;
 
 /// A circle constructed of a centerpoint which is a coordinate and a radius in
 /// kilometers.
-#[derive(Debug)]
-pub struct Circle<'a> {
+#[derive(Debug, Clone, PartialEq)]
+pub struct Circle {
     /// The coordinates of the centerpoint
-    pub centerpoint: &'a Coordinate,
+    pub centerpoint: Coordinate,
     /// The radius in kilometers
     pub radius: f64,
 }
 
-impl Circle<'_> {
-    pub fn to_string(&self) -> String {
-        format!("{},{}", self.centerpoint.to_string(), self.radius)
+impl Circle {
+    /// Creates a circle with a radius in meters, which is converted to the kilometers of
+    /// `radius`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let depot = Coordinate { latitude: 51.520847, longitude: -0.195521 };
+    /// let zone = Circle::from_meters(depot, 500.0);
+    /// assert_eq!(zone.radius, 0.5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_meters(centerpoint: impl Into<Coordinate>, meters: f64) -> Circle {
+        Circle {
+            centerpoint: centerpoint.into(),
+            radius: meters / METERS_PER_KILOMETER,
+        }
+    }
+
+    /// Creates a circle with a radius in kilometers, the unit of `radius`.
+    pub fn from_kilometers(centerpoint: impl Into<Coordinate>, kilometers: f64) -> Circle {
+        Circle {
+            centerpoint: centerpoint.into(),
+            radius: kilometers,
+        }
+    }
+
+    /// Creates a circle with a radius in international miles, which is converted to the
+    /// kilometers of `radius`.
+    pub fn from_miles(centerpoint: impl Into<Coordinate>, miles: f64) -> Circle {
+        Circle::from_meters(centerpoint, miles * METERS_PER_MILE)
+    }
+
+    /// Return the radius in meters.
+    pub fn radius_m(&self) -> f64 {
+        self.radius * METERS_PER_KILOMETER
+    }
+
+    /// Return the radius in international miles.
+    pub fn radius_miles(&self) -> f64 {
+        self.radius_m() / METERS_PER_MILE
+    }
+
+    /// Return a polygon of `segments` coordinates on the edge of the circle, e.g. to export the
+    /// clip as GeoJSON or to use it as a `clip-to-polygon`. The number of segments is kept
+    /// within 3 and 24, so the closed ring fits the 25 pairs of the what3words API. The
+    /// coordinates run counter-clockwise, starting due north of the centerpoint.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let depot = Coordinate { latitude: 51.520847, longitude: -0.195521 };
+    /// let polygon = Circle::from_meters(depot, 500.0).to_polygon(24);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_polygon(&self, segments: usize) -> Polygon {
+        let segments = segments.clamp(3, MAX_POLYGON_PAIRS - 1);
+        let step = 360.0 / segments as f64;
+        Polygon {
+            coordinates: (0..segments)
+                .map(|i| {
+                    let bearing = (360.0 - i as f64 * step) % 360.0;
+                    destination(&self.centerpoint, bearing, self.radius_m())
+                })
+                .collect(),
+        }
+    }
+
+    /// Return the circle as Well-Known Text, approximated by the polygon of 24 segments of
+    /// `Circle::to_polygon`, as WKT has no circles.
+    pub fn to_wkt(&self) -> String {
+        self.to_polygon(MAX_POLYGON_PAIRS - 1).to_wkt()
+    }
+
+    /// Return whether the coordinate lies within the circle, measured along the great circle
+    /// from the centerpoint.
+    pub fn contains(&self, coordinate: &Coordinate) -> bool {
+        haversine_distance(&self.centerpoint, coordinate) <= self.radius_m()
+    }
+}
+
+impl fmt::Display for Circle {
+    /// Format the circle in the form `"<latitude>,<longitude>,<radius>"`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.centerpoint, self.radius)
     }
 }
@@ -1,19 +1,403 @@
 //! The Coordinate struct which is used in the `Circle`, `BoundingBox` and `Polygon` structs.
 //! A coordinate is made up of a latitude and a longitude and can be printed as
 //! `<latitude>,<longitude>`.
+//!
+//! `Coordinate::warning` spots the inputs which the what3words API answers confusingly, such as
+//! NaN, swapped latitude and longitude or the `0,0` placeholder of a missing value.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Deserialize;
+
+use crate::coordinate_format::{parse_dms, parse_iso6709};
+use crate::error::W3WError;
+use crate::geo_utils::{
+    destination, haversine_distance, initial_bearing, meters_per_degree_latitude,
+    meters_per_degree_longitude, midpoint, wrap_longitude,
+};
+use crate::polygon::wkt_point;
 
 /// Represents geographical coordinates with latitude and longitude.
-#[derive(Debug)]
+/// It deserializes from the `{"lat": .., "lng": ..}` objects of the what3words API.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
 pub struct Coordinate {
     /// The latitude value
+    #[serde(rename = "lat")]
     pub latitude: f64,
     /// The longitude value
+    #[serde(rename = "lng")]
     pub longitude: f64,
 }
 
 impl Coordinate {
-    /// Return the coordinate as a string in the form `"<latitude>,<longitude>"`
-    pub fn to_string(&self) -> String {
-        format!("{},{}", self.latitude, self.longitude)
+    /// Creates a coordinate after checking it, so a latitude beyond the poles, a longitude
+    /// beyond 180, NaN or a swapped pair is a `W3WError::Validation`. The fields stay public to
+    /// build a coordinate without checks, e.g. to hold a longitude beyond 180 of a bounding box.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// let coordinate = Coordinate::new(51.521251, -0.203586)?;
+    /// assert!(Coordinate::new(123.0, 500.0).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(latitude: f64, longitude: f64) -> Result<Coordinate, W3WError> {
+        let coordinate = Coordinate {
+            latitude,
+            longitude,
+        };
+        coordinate.validate()?;
+        Ok(coordinate)
+    }
+
+    /// Parse a coordinate in degrees, minutes and seconds, like `51°30'26"N 0°7'39"W` or
+    /// `N 51 30.43 W 0 7.65`, and check it like `Coordinate::new`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// let coordinate = Coordinate::from_dms("51°30'26\"N 0°7'39\"W")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_dms(s: &str) -> Result<Coordinate, W3WError> {
+        parse_dms(s)
+    }
+
+    /// Parse a coordinate in the ISO 6709 format, like `+51.5074-000.1278/` or
+    /// `+513026-0000739/`, and check it like `Coordinate::new`.
+    pub fn from_iso6709(s: &str) -> Result<Coordinate, W3WError> {
+        parse_iso6709(s)
+    }
+
+    /// Return the great-circle distance in meters to `other`, calculated with the haversine
+    /// formula on a spherical earth. The error is below 0.5 %, which is plenty to rank squares
+    /// or check distances of a few kilometers.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let depot = Coordinate { latitude: 51.520847, longitude: -0.195521 };
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let address = w3_client.convert_to_coordinates_typed("filled.count.soap", &ConvertToCoordinatesOptions::default())?;
+    /// let distance_m = address.coordinates.distance_to(&depot);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn distance_to(&self, other: &Coordinate) -> f64 {
+        haversine_distance(self, other)
+    }
+
+    /// Return the initial bearing to `other` in degrees, clockwise from north within 0 and 360,
+    /// following the great circle. `0` is due north and `90` due east.
+    pub fn bearing_to(&self, other: &Coordinate) -> f64 {
+        initial_bearing(self, other)
+    }
+
+    /// Return the point halfway along the great circle to `other`.
+    pub fn midpoint(&self, other: &Coordinate) -> Coordinate {
+        midpoint(self, other)
+    }
+
+    /// Return the point reached by travelling `meters` along the great circle which starts with
+    /// `bearing`, in degrees clockwise from north.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let depot = Coordinate { latitude: 51.520847, longitude: -0.195521 };
+    /// let one_km_east = depot.destination(90.0, 1000.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn destination(&self, bearing: f64, meters: f64) -> Coordinate {
+        destination(self, bearing, meters)
+    }
+
+    /// Return the coordinate as a Well-Known Text point, e.g. `POINT(-0.2 51.52)` with the
+    /// longitude first.
+    pub fn to_wkt(&self) -> String {
+        format!("POINT({})", wkt_point(self))
+    }
+
+    /// Return the coordinate moved by a random distance in a random direction, uniformly within
+    /// `radius_m` meters, e.g. to convert an approximate location for analytics without storing
+    /// the exact position. The random numbers come from the random source of the operating
+    /// system.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the operating system has no random source, see `getrandom`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let options = ConvertTo3WAOptions::default();
+    /// # let position = Coordinate { latitude: 51.520847, longitude: -0.195521 };
+    /// let approximate = position.jittered(100.0).snapped(50.0);
+    /// let address = w3_client.convert_to_3wa_typed(approximate, &options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn jittered(&self, radius_m: f64) -> Coordinate {
+        self.jittered_with(radius_m, random_unit)
+    }
+
+    /// Return the coordinate moved uniformly within `radius_m` meters like `jittered`, with the
+    /// random numbers within 0 and 1 taken from `random`, e.g. a seeded generator.
+    pub fn jittered_with(&self, radius_m: f64, mut random: impl FnMut() -> f64) -> Coordinate {
+        let distance_m = radius_m * random().sqrt();
+        let bearing = 360.0 * random();
+        self.destination(bearing, distance_m)
+    }
+
+    /// Return the nearest point of a grid of `grid_m` meters, so all positions within a cell
+    /// give the same coordinate. The rows are `grid_m` meters apart and the columns are
+    /// `grid_m` meters apart at the latitude of the row.
+    pub fn snapped(&self, grid_m: f64) -> Coordinate {
+        let snap = |value: f64, step: f64| (value / step).round() * step;
+        let latitude =
+            snap(self.latitude, grid_m / meters_per_degree_latitude()).clamp(-90.0, 90.0);
+        let lng_step = grid_m / meters_per_degree_longitude(latitude);
+        let longitude = match snap(self.longitude, lng_step) {
+            longitude if longitude.is_finite() => longitude,
+            _ => self.longitude,
+        };
+        Coordinate {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Return the problem with this coordinate, if any. `NotFinite`, `OutOfRange` and `Swapped`
+    /// are errors which the client rejects before sending a request, `NullIsland` is only a
+    /// warning because `0,0` is a valid, if unlikely, location.
+    pub fn warning(&self) -> Option<CoordinateWarning> {
+        let in_range = |latitude: f64, longitude: f64| {
+            (-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)
+        };
+        if !self.latitude.is_finite() || !self.longitude.is_finite() {
+            Some(CoordinateWarning::NotFinite)
+        } else if in_range(self.latitude, self.longitude) {
+            (self.latitude == 0.0 && self.longitude == 0.0).then_some(CoordinateWarning::NullIsland)
+        } else if in_range(self.longitude, self.latitude) {
+            Some(CoordinateWarning::Swapped)
+        } else {
+            Some(CoordinateWarning::OutOfRange)
+        }
+    }
+
+    /// Return the coordinate with its longitude wrapped into the range from -180 to 180, as GPS
+    /// receivers and tile math sometimes produce longitudes like 181 or -540. A latitude beyond
+    /// the poles cannot be wrapped and is reported as a `W3WError::Validation`, like a coordinate
+    /// which is not finite.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// let coordinate = Coordinate { latitude: 51.5, longitude: 359.9 }.normalized()?;
+    /// assert!((coordinate.longitude + 0.1).abs() < 1e-9);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn normalized(&self) -> Result<Coordinate, W3WError> {
+        let coordinate = Coordinate {
+            latitude: self.latitude,
+            longitude: match self.longitude {
+                longitude if (-180.0..=180.0).contains(&longitude) => longitude,
+                longitude => wrap_longitude(longitude),
+            },
+        };
+        coordinate.validate()?;
+        Ok(coordinate)
+    }
+
+    /// Return the coordinate with its latitude and longitude rounded to `decimals` decimal
+    /// places. Six decimals are about 0.1 m, well within a 3 meter square.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// let rounded = Coordinate { latitude: 51.52084712, longitude: -0.19552149 }.rounded(6);
+    /// assert_eq!(rounded.to_string(), "51.520847,-0.195521");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn rounded(&self, decimals: u8) -> Coordinate {
+        let factor = 10f64.powi(i32::from(decimals));
+        let round = |value: f64| match (value * factor).round() / factor {
+            rounded if rounded.is_finite() => rounded,
+            _ => value,
+        };
+        Coordinate {
+            latitude: round(self.latitude),
+            longitude: round(self.longitude),
+        }
+    }
+
+    /// Return a validation error when this coordinate has a `warning` which is an error.
+    pub(crate) fn validate(&self) -> Result<(), W3WError> {
+        self.validate_warnings(CoordinateWarning::is_error)
+    }
+
+    /// Return a validation error when this coordinate has a `warning` which is `rejected`.
+    pub(crate) fn validate_warnings(
+        &self,
+        rejected: impl Fn(&CoordinateWarning) -> bool,
+    ) -> Result<(), W3WError> {
+        match self.warning() {
+            Some(warning) if rejected(&warning) => Err(W3WError::Validation(format!(
+                "invalid coordinate '{}': {}",
+                self, warning
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Return a random number within 0 (included) and 1 (excluded), see `random_u64`.
+fn random_unit() -> f64 {
+    (random_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Return a random number from the random source of the operating system.
+pub(crate) fn random_u64() -> u64 {
+    let mut bytes = [0; 8];
+    getrandom::getrandom(&mut bytes).expect("the operating system has no random source");
+    u64::from_le_bytes(bytes)
+}
+
+/// A problem with a `Coordinate`, as found by `Coordinate::warning`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordinateWarning {
+    /// The latitude or longitude is NaN or infinite
+    NotFinite,
+    /// The latitude is not within -90 and 90 or the longitude is not within -180 and 180
+    OutOfRange,
+    /// The coordinate is out of range, but would be in range with latitude and longitude swapped
+    Swapped,
+    /// The coordinate is `0,0`, which usually stands in for a missing value
+    NullIsland,
+}
+
+impl CoordinateWarning {
+    /// Whether the coordinate cannot be sent to the what3words API at all.
+    pub fn is_error(&self) -> bool {
+        !matches!(self, CoordinateWarning::NullIsland)
+    }
+}
+
+impl fmt::Display for CoordinateWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            CoordinateWarning::NotFinite => "the latitude or longitude is not a finite number",
+            CoordinateWarning::OutOfRange => "the latitude or longitude is out of range",
+            CoordinateWarning::Swapped => "the latitude and longitude seem to be swapped",
+            CoordinateWarning::NullIsland => {
+                "the coordinate is 0,0, which is often a missing value"
+            }
+        };
+        f.write_str(message)
+    }
+}
+
+impl FromStr for Coordinate {
+    type Err = W3WError;
+
+    /// Parse a `"<latitude>,<longitude>"` pair, the format of `to_string`, with optional
+    /// whitespace around the numbers. The error names the part which is wrong. The coordinate
+    /// is not checked, see `Coordinate::warning`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| {
+            W3WError::Validation(format!(
+                "'{}' is not a latitude,longitude pair: {}",
+                s, reason
+            ))
+        };
+        let (latitude, longitude) = s
+            .split_once(',')
+            .ok_or_else(|| invalid(String::from("expected a comma")))?;
+        if longitude.contains(',') {
+            return Err(invalid(String::from("expected a single comma")));
+        }
+        let number = |name: &str, value: &str| {
+            value
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| invalid(format!("the {} '{}' is not a number", name, value.trim())))
+        };
+        Ok(Coordinate {
+            latitude: number("latitude", latitude)?,
+            longitude: number("longitude", longitude)?,
+        })
+    }
+}
+
+impl TryFrom<&str> for Coordinate {
+    type Error = W3WError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<String> for Coordinate {
+    type Error = W3WError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<(f64, f64)> for Coordinate {
+    /// Create a coordinate from a `(latitude, longitude)` tuple, latitude first like the
+    /// what3words API. GeoJSON and many geo crates put the longitude first. The coordinate is
+    /// not checked, see `Coordinate::new`.
+    fn from((latitude, longitude): (f64, f64)) -> Self {
+        Coordinate {
+            latitude,
+            longitude,
+        }
+    }
+}
+
+impl From<[f64; 2]> for Coordinate {
+    /// Create a coordinate from a `[latitude, longitude]` array, latitude first like the
+    /// what3words API. The coordinate is not checked, see `Coordinate::new`.
+    fn from([latitude, longitude]: [f64; 2]) -> Self {
+        Coordinate {
+            latitude,
+            longitude,
+        }
+    }
+}
+
+impl From<&Coordinate> for Coordinate {
+    fn from(coordinate: &Coordinate) -> Self {
+        *coordinate
+    }
+}
+
+impl fmt::Display for Coordinate {
+    /// Format the coordinate in the form `"<latitude>,<longitude>"`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.latitude, self.longitude)
     }
 }
@@ -0,0 +1,254 @@
+//! Parsing of coordinates in degrees, minutes and seconds, like `51°30'26"N 0°7'39"W`, and in
+//! the ISO 6709 format, like `+51.5074-000.1278/`, as field teams and GPS devices write them.
+//! Both return a checked `Coordinate`, see `Coordinate::new`.
+
+use crate::coordinate::Coordinate;
+use crate::error::W3WError;
+
+/// A part of a coordinate in degrees, minutes and seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(f64),
+    Hemisphere(char),
+}
+
+/// Parse a coordinate in degrees, minutes and seconds with a hemisphere letter before or after
+/// each half, e.g. `51°30'26"N 0°7'39"W`, `N 51 30.43 W 0 7.65` or `51.5074 N, 0.1278 W`.
+/// Minutes and seconds may be left out and the last number of a half may have decimals.
+pub(crate) fn parse_dms(s: &str) -> Result<Coordinate, W3WError> {
+    let invalid = |reason: &str| {
+        W3WError::Validation(format!(
+            "'{}' is not a coordinate in degrees, minutes and seconds: {}",
+            s, reason
+        ))
+    };
+    let tokens = tokenize(s).ok_or_else(|| invalid("unexpected character"))?;
+    let hemispheres: Vec<usize> = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| matches!(token, Token::Hemisphere(_)))
+        .map(|(position, _)| position)
+        .collect();
+    let [first, second] = hemispheres[..] else {
+        return Err(invalid("expected two hemisphere letters"));
+    };
+    let (numbers, letters) = if first == 0 {
+        (
+            [&tokens[1..second], &tokens[second + 1..]],
+            [tokens[first], tokens[second]],
+        )
+    } else if second == tokens.len() - 1 {
+        (
+            [&tokens[..first], &tokens[first + 1..second]],
+            [tokens[first], tokens[second]],
+        )
+    } else {
+        return Err(invalid(
+            "expected the hemisphere letters all before or all after the numbers",
+        ));
+    };
+
+    let (mut latitude, mut longitude) = (None, None);
+    for (numbers, letter) in numbers.into_iter().zip(letters) {
+        let degrees = degrees(numbers).ok_or_else(|| {
+            invalid("expected degrees, minutes and seconds with minutes and seconds below 60")
+        })?;
+        match letter {
+            Token::Hemisphere('N') if latitude.is_none() => latitude = Some(degrees),
+            Token::Hemisphere('S') if latitude.is_none() => latitude = Some(-degrees),
+            Token::Hemisphere('E') if longitude.is_none() => longitude = Some(degrees),
+            Token::Hemisphere('W') if longitude.is_none() => longitude = Some(-degrees),
+            _ => return Err(invalid("expected one of N and S and one of E and W")),
+        }
+    }
+    match (latitude, longitude) {
+        (Some(latitude), Some(longitude)) => Coordinate::new(latitude, longitude),
+        _ => Err(invalid("expected one of N and S and one of E and W")),
+    }
+}
+
+/// Parse a coordinate in the ISO 6709 format: a signed latitude of the form `±DD`, `±DDMM` or
+/// `±DDMMSS` and a signed longitude of the form `±DDD`, `±DDDMM` or `±DDDMMSS`, each with
+/// optional decimals, e.g. `+51.5074-000.1278/` or `+513026-0000739/`. An altitude and a
+/// `CRS` suffix are ignored and the closing `/` is optional.
+pub(crate) fn parse_iso6709(s: &str) -> Result<Coordinate, W3WError> {
+    let invalid = || W3WError::Validation(format!("'{}' is not an ISO 6709 coordinate", s));
+    let trimmed = s.trim();
+    let trimmed = trimmed.strip_suffix('/').unwrap_or(trimmed);
+    let (latitude, rest) = signed_part(trimmed).ok_or_else(invalid)?;
+    let (longitude, rest) = signed_part(rest).ok_or_else(invalid)?;
+    let rest = match signed_part(rest) {
+        Some((_altitude, rest)) => rest,
+        None => rest,
+    };
+    if !rest.is_empty() && !rest.starts_with("CRS") {
+        return Err(invalid());
+    }
+    let latitude = iso6709_degrees(latitude, 2).ok_or_else(invalid)?;
+    let longitude = iso6709_degrees(longitude, 3).ok_or_else(invalid)?;
+    Coordinate::new(latitude, longitude)
+}
+
+/// Split the input into numbers and uppercase hemisphere letters, skipping whitespace, commas
+/// and the symbols for degrees, minutes and seconds. Returns `None` for any other character.
+fn tokenize(s: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '0'..='9' | '.' => {
+                let mut number = String::from(c);
+                while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit() || **c == '.') {
+                    number.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Number(number.parse().ok()?));
+            }
+            'N' | 'S' | 'E' | 'W' | 'n' | 's' | 'e' | 'w' => {
+                tokens.push(Token::Hemisphere(c.to_ascii_uppercase()))
+            }
+            '°' | 'º' | '\'' | '′' | '’' | '"' | '″' | '”' | ',' => {}
+            c if c.is_whitespace() => {}
+            _ => return None,
+        }
+    }
+    Some(tokens)
+}
+
+/// Return the degrees of one to three numbers of degrees, minutes and seconds. Only the last
+/// number may have decimals, and minutes and seconds must be below 60.
+fn degrees(numbers: &[Token]) -> Option<f64> {
+    let numbers: Vec<f64> = numbers
+        .iter()
+        .map(|token| match token {
+            Token::Number(number) => Some(*number),
+            Token::Hemisphere(_) => None,
+        })
+        .collect::<Option<_>>()?;
+    if numbers.is_empty()
+        || numbers.len() > 3
+        || numbers[..numbers.len() - 1]
+            .iter()
+            .any(|number| number.fract() != 0.0)
+        || numbers[1..].iter().any(|number| *number >= 60.0)
+    {
+        return None;
+    }
+    Some(
+        numbers
+            .iter()
+            .zip([1.0, 60.0, 3600.0])
+            .map(|(number, divisor)| number / divisor)
+            .sum(),
+    )
+}
+
+/// Split a leading `+` or `-` with the digits and decimals which follow it from the input.
+fn signed_part(s: &str) -> Option<(&str, &str)> {
+    if !s.starts_with(['+', '-']) {
+        return None;
+    }
+    let end = s[1..]
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .map_or(s.len(), |position| position + 1);
+    Some(s.split_at(end))
+}
+
+/// Return the degrees of a signed ISO 6709 latitude or longitude, whose degrees take
+/// `degree_digits` digits, followed by two digits of minutes and two of seconds if present.
+fn iso6709_degrees(part: &str, degree_digits: usize) -> Option<f64> {
+    let (sign, number) = part.split_at(1);
+    let digits = number.find('.').unwrap_or(number.len());
+    if number.is_empty() || number[digits..].contains(|c: char| c != '.' && !c.is_ascii_digit()) {
+        return None;
+    }
+    let value: f64 = number.parse().ok()?;
+    let degrees = match digits.checked_sub(degree_digits)? {
+        0 => value,
+        2 => {
+            let minutes = value % 100.0;
+            (minutes < 60.0).then_some(((value - minutes) / 100.0) + minutes / 60.0)?
+        }
+        4 => {
+            let seconds = value % 100.0;
+            let minutes = ((value - seconds) / 100.0) % 100.0;
+            let degrees = ((value - seconds) / 100.0 - minutes) / 100.0;
+            (minutes < 60.0 && seconds < 60.0)
+                .then_some(degrees + minutes / 60.0 + seconds / 3600.0)?
+        }
+        _ => return None,
+    };
+    Some(if sign == "-" { -degrees } else { degrees })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_dms, parse_iso6709};
+
+    fn assert_close(actual: (f64, f64), expected: (f64, f64)) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-6 && (actual.1 - expected.1).abs() < 1e-6,
+            "{:?} is not {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_parse_dms() {
+        for input in [
+            "51°30'26\"N 0°7'39\"W",
+            "51° 30′ 26″ N, 0° 7′ 39″ W",
+            "N 51 30 26 W 0 7 39",
+            "0°7'39\"W 51°30'26\"N",
+            "51 30.433333 n 0 7.65 w",
+        ] {
+            let coordinate = parse_dms(input).unwrap();
+            assert_close(
+                (coordinate.latitude, coordinate.longitude),
+                (51.507222, -0.1275),
+            );
+        }
+        let coordinate = parse_dms("33.8688 S 151.2093 E").unwrap();
+        assert_close(
+            (coordinate.latitude, coordinate.longitude),
+            (-33.8688, 151.2093),
+        );
+        for input in [
+            "51°30'26\"N",
+            "51°30'26\"N 0°7'39\"N",
+            "51°75'N 0°7'W",
+            "51.5°30'N 0°7'W",
+            "91°N 0°E",
+            "N 51 30 0 7 W",
+            "filled.count.soap",
+        ] {
+            assert!(parse_dms(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_iso6709() {
+        for (input, expected) in [
+            ("+51.5074-000.1278/", (51.5074, -0.1278)),
+            ("+51.5074-000.1278", (51.5074, -0.1278)),
+            ("+5130.444-00007.668/", (51.5074, -0.1278)),
+            ("+513026.64-0000740.08/", (51.5074, -0.127800)),
+            ("-33.8688+151.2093+58CRSWGS_84/", (-33.8688, 151.2093)),
+            ("+51-001/", (51.0, -1.0)),
+        ] {
+            let coordinate = parse_iso6709(input).unwrap();
+            assert_close((coordinate.latitude, coordinate.longitude), expected);
+        }
+        for input in [
+            "51.5074-000.1278/",
+            "+51.5074/",
+            "+5175-00007/",
+            "+51.5074-0.1278/",
+            "+91-000/",
+            "+51.5074-000.1278 north/",
+        ] {
+            assert!(parse_iso6709(input).is_err(), "{}", input);
+        }
+    }
+}
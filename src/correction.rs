@@ -0,0 +1,15 @@
+//! The `Correction` is the verdict of `W3WClient::suggest_correction` on user input which may be
+//! a misspelled three word address.
+
+use crate::suggestion::Suggestion;
+
+/// Whether user input is a three word address, and if not, what it may have meant.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Correction {
+    /// The input is an existing three word address, normalized to `word.word.word`
+    Valid(String),
+    /// The input is close to a three word address; these are the suggestions for it
+    Candidates(Vec<Suggestion>),
+    /// The input does not look like a three word address, or nothing close to it exists
+    NotAnAddress,
+}
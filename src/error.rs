@@ -0,0 +1,308 @@
+//! The `W3WError` is returned by all `W3WClient` calls when something went wrong, either before
+//! the request was sent or in the response of the what3words API.
+//!
+//! URLs in errors are redacted: the value of the `key` query parameter is replaced, so API keys
+//! do not leak into logs captured by error reporters.
+//!
+//! An error response is read into an `ErrorResponse`, including the start of its body, which
+//! holds the explanation of the what3words API.
+//!
+//! The messages of errors are meant for developers and in English, like the explanations of the
+//! what3words API. `W3WError::user_message` returns a short message for end users instead, from
+//! a catalog in a few languages.
+
+use std::error::Error;
+use std::fmt;
+use std::io::Read;
+
+use reqwest::blocking::Response;
+use reqwest::{StatusCode, Url};
+
+/// The value which replaces the API key in redacted output.
+pub(crate) const REDACTED: &str = "<redacted>";
+
+/// The messages for end users, per language: the input is invalid, the quota ran out, the
+/// service is unavailable for now, and any other error. English comes first, as the fallback.
+const USER_MESSAGES: [(&str, [&str; 4]); 5] = [
+    (
+        "en",
+        [
+            "This address or location is not valid. Please check it and try again.",
+            "The address service has reached its usage limit. Please try again later.",
+            "The address service is temporarily unavailable. Please try again in a moment.",
+            "Something went wrong while looking up the address.",
+        ],
+    ),
+    (
+        "nl",
+        [
+            "Dit adres of deze locatie is niet geldig. Controleer het en probeer het opnieuw.",
+            "De adresservice heeft zijn gebruikslimiet bereikt. Probeer het later opnieuw.",
+            "De adresservice is tijdelijk niet beschikbaar. Probeer het zo opnieuw.",
+            "Er ging iets mis bij het opzoeken van het adres.",
+        ],
+    ),
+    (
+        "de",
+        [
+            "Diese Adresse oder dieser Ort ist ungültig. Bitte prüfen Sie die Eingabe und versuchen Sie es erneut.",
+            "Der Adressdienst hat sein Nutzungslimit erreicht. Bitte versuchen Sie es später erneut.",
+            "Der Adressdienst ist vorübergehend nicht erreichbar. Bitte versuchen Sie es gleich noch einmal.",
+            "Beim Nachschlagen der Adresse ist ein Fehler aufgetreten.",
+        ],
+    ),
+    (
+        "fr",
+        [
+            "Cette adresse ou cet emplacement n'est pas valide. Veuillez vérifier et réessayer.",
+            "Le service d'adresses a atteint sa limite d'utilisation. Veuillez réessayer plus tard.",
+            "Le service d'adresses est temporairement indisponible. Veuillez réessayer dans un instant.",
+            "Une erreur s'est produite lors de la recherche de l'adresse.",
+        ],
+    ),
+    (
+        "es",
+        [
+            "Esta dirección o ubicación no es válida. Compruébela e inténtelo de nuevo.",
+            "El servicio de direcciones ha alcanzado su límite de uso. Inténtelo de nuevo más tarde.",
+            "El servicio de direcciones no está disponible temporalmente. Inténtelo de nuevo en un momento.",
+            "Se produjo un error al buscar la dirección.",
+        ],
+    ),
+];
+
+/// All errors which can occur while calling the what3words API.
+#[derive(Debug)]
+pub enum W3WError {
+    /// The request was not sent because its input is invalid, e.g. a malformed host
+    Validation(String),
+    /// The request could not be sent or its response could not be read
+    Request(reqwest::Error),
+    /// The what3words API responded with a status code between 400 and 599
+    Response(ErrorResponse),
+    /// The body of a successful response does not have the expected shape
+    Decode(DecodeError),
+}
+
+/// A response body which could not be decoded, with the JSON path at which decoding failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    /// The JSON pointer to the offending value, e.g. `/coordinates/lat`, empty for the whole body
+    pub path: String,
+    /// What is wrong with the value
+    pub message: String,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{} at '{}'", self.message, self.path)
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// A response of the what3words API with a status code between 400 and 599.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorResponse {
+    /// The status code of the response
+    pub status: StatusCode,
+    /// The URL of the request, with the API key redacted
+    pub url: String,
+    /// The start of the body, at most `W3WClient::error_body_limit` bytes
+    pub body: String,
+}
+
+impl ErrorResponse {
+    /// Read the status, URL and at most `body_limit` bytes of the body of a response. A body
+    /// which cannot be read is left empty, as the status is the error that matters.
+    pub(crate) fn read(response: Response, body_limit: usize) -> Self {
+        let status = response.status();
+        let mut url = response.url().clone();
+        redact_url(&mut url);
+        let mut body = Vec::new();
+        let _ = response.take(body_limit as u64).read_to_end(&mut body);
+        ErrorResponse {
+            status,
+            url: url.into(),
+            body: String::from_utf8_lossy(&body).into_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ErrorResponse {
+    /// Format the status code, followed by the body if there is one
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "status code: {}", self.status)?;
+        if !self.body.is_empty() {
+            write!(f, ", body: {}", self.body)?;
+        }
+        Ok(())
+    }
+}
+
+impl W3WError {
+    /// Whether the error may go away when the request is repeated: the connection failed or
+    /// timed out, or the what3words API answered `429 Too Many Requests` or a server error.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            W3WError::Request(error) => error.is_timeout() || error.is_connect(),
+            W3WError::Response(response) => {
+                response.status == StatusCode::TOO_MANY_REQUESTS
+                    || response.status.is_server_error()
+            }
+            W3WError::Validation(_) | W3WError::Decode(_) => false,
+        }
+    }
+
+    /// Whether the what3words API answered `402 Payment Required`: the API key ran out of
+    /// quota.
+    pub fn is_quota_exceeded(&self) -> bool {
+        matches!(self, W3WError::Response(response) if response.status == StatusCode::PAYMENT_REQUIRED)
+    }
+
+    /// Return a short message for end users in `language`, e.g. `nl` or `fr-BE`, which says
+    /// whether the input is invalid, the quota ran out or the service is unavailable for now,
+    /// without the details of the error. The messages are in English (`en`), Dutch (`nl`),
+    /// German (`de`), French (`fr`) and Spanish (`es`); any other language gets English.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// if let Err(error) = w3_client.convert_to_coordinates_typed("filled.count", &ConvertToCoordinatesOptions::default()) {
+    ///     eprintln!("{}", error);
+    ///     println!("{}", error.user_message("nl"));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn user_message(&self, language: &str) -> &'static str {
+        let kind = match self {
+            W3WError::Validation(_) => 0,
+            W3WError::Response(response) if response.status == StatusCode::BAD_REQUEST => 0,
+            _ if self.is_quota_exceeded() => 1,
+            _ if self.is_transient() => 2,
+            _ => 3,
+        };
+        let language = language
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let (_, messages) = USER_MESSAGES
+            .iter()
+            .find(|(code, _)| *code == language)
+            .unwrap_or(&USER_MESSAGES[0]);
+        messages[kind]
+    }
+}
+
+impl fmt::Display for W3WError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            W3WError::Validation(message) => write!(f, "Invalid request: {}", message),
+            W3WError::Request(error) => write!(f, "Request failed: {}", error),
+            W3WError::Response(response) => {
+                write!(f, "The response returned an error, {}", response)
+            }
+            W3WError::Decode(error) => write!(f, "The response could not be decoded: {}", error),
+        }
+    }
+}
+
+impl Error for W3WError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            W3WError::Request(error) => Some(error),
+            W3WError::Decode(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for W3WError {
+    fn from(mut error: reqwest::Error) -> Self {
+        if let Some(url) = error.url_mut() {
+            redact_url(url);
+        }
+        W3WError::Request(error)
+    }
+}
+
+/// Replace the value of the `key` query parameter of the URL, if any.
+pub(crate) fn redact_url(url: &mut Url) {
+    if !url.query_pairs().any(|(name, _)| name == "key") {
+        return;
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| {
+            let value = if name == "key" {
+                REDACTED.to_string()
+            } else {
+                value.into_owned()
+            };
+            (name.into_owned(), value)
+        })
+        .collect();
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::{StatusCode, Url};
+
+    use super::{redact_url, ErrorResponse, W3WError};
+
+    #[test]
+    fn test_redact_url() {
+        let mut url = Url::parse("https://test.com/v3/autosuggest?key=secret&input=a.b.c").unwrap();
+        redact_url(&mut url);
+        assert_eq!(
+            url.as_str(),
+            "https://test.com/v3/autosuggest?key=%3Credacted%3E&input=a.b.c"
+        );
+
+        let mut url = Url::parse("https://test.com/v3/autosuggest?input=a.b.c").unwrap();
+        redact_url(&mut url);
+        assert_eq!(url.as_str(), "https://test.com/v3/autosuggest?input=a.b.c");
+    }
+
+    #[test]
+    fn test_user_message_is_localized_by_kind() {
+        let response = |status| {
+            W3WError::Response(ErrorResponse {
+                status,
+                url: String::new(),
+                body: String::new(),
+            })
+        };
+        let invalid = W3WError::Validation(String::from("'filled.count' is not an address"));
+        assert_eq!(
+            invalid.user_message("en"),
+            "This address or location is not valid. Please check it and try again."
+        );
+        assert_eq!(
+            response(StatusCode::BAD_REQUEST).user_message("fr-BE"),
+            invalid.user_message("fr")
+        );
+        assert_eq!(
+            response(StatusCode::PAYMENT_REQUIRED).user_message("NL"),
+            "De adresservice heeft zijn gebruikslimiet bereikt. Probeer het later opnieuw."
+        );
+        assert_eq!(
+            response(StatusCode::SERVICE_UNAVAILABLE).user_message("de_AT"),
+            "Der Adressdienst ist vorübergehend nicht erreichbar. Bitte versuchen Sie es gleich noch einmal."
+        );
+        assert_eq!(
+            response(StatusCode::UNAUTHORIZED).user_message("ja"),
+            "Something went wrong while looking up the address."
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! An `EventListener` on a `W3WClient` is told about the caches, retries and quota of the client
+//! as they happen, e.g. to raise an alert or show a degraded-service banner without parsing the
+//! log.
+
+use std::time::Duration;
+
+use crate::error::W3WError;
+
+/// The cache of a `W3WClient` an event is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheKind {
+    /// The `address_cache`, of `convert_to_3wa_typed`, `convert_to_coordinates_typed` and the
+    /// calls built on them
+    Address,
+    /// The `autosuggest_cache`, of `autosuggest_typed`
+    Autosuggest,
+}
+
+/// Receives the events of a `W3WClient`. Every method does nothing by default, so a listener
+/// only implements the events it needs. The methods are called on the thread of the call,
+/// which may be a worker thread of a bulk call, so they should return quickly.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use what3words::*;
+/// # fn main() -> Result<(), what3words::W3WError> {
+/// struct Alerts;
+///
+/// impl EventListener for Alerts {
+///     fn circuit_opened(&self, pause: Duration) {
+///         eprintln!("out of quota, pausing for {:?}", pause);
+///     }
+/// }
+///
+/// let mut w3_client = W3WClient::new("your_api_key");
+/// w3_client.event_listener = Some(Box::new(Alerts));
+/// # Ok(())
+/// # }
+/// ```
+pub trait EventListener: Send + Sync {
+    /// A typed call was answered from `cache`, without a request.
+    fn cache_hit(&self, _cache: CacheKind) {}
+
+    /// A typed call was not found in `cache`, so a request is sent.
+    fn cache_miss(&self, _cache: CacheKind) {}
+
+    /// A call of a bulk call failed with an `error` which `is_transient`, and is tried again
+    /// after `delay`. `attempt` is the number of the failed attempt, starting at 1.
+    fn retry_scheduled(&self, _attempt: u32, _delay: Duration, _error: &W3WError) {}
+
+    /// A bulk call ran out of quota and pauses all of its calls for `pause`, see
+    /// `W3WClient::quota_pause`.
+    fn circuit_opened(&self, _pause: Duration) {}
+
+    /// A key of the `key_pool` ran out of quota and is skipped from now on. `keys_left` keys
+    /// are still available; at zero, the calls fail with the quota error.
+    fn quota_low(&self, _keys_left: usize) {}
+}
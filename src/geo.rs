@@ -0,0 +1,119 @@
+//! Conversions between the shapes of this crate and those of the `geo-types` crate, behind the
+//! `geo` feature, so coordinates and clips can be passed to and from georust code as is.
+//!
+//! `geo-types` puts the longitude in `x` and the latitude in `y`.
+
+use geo_types::{Coord, LineString, Point, Rect};
+
+use crate::bounding_box::BoundingBox;
+use crate::coordinate::Coordinate;
+use crate::polygon::Polygon;
+
+impl From<Coordinate> for Coord {
+    fn from(coordinate: Coordinate) -> Self {
+        Coord {
+            x: coordinate.longitude,
+            y: coordinate.latitude,
+        }
+    }
+}
+
+impl From<Coord> for Coordinate {
+    fn from(coord: Coord) -> Self {
+        Coordinate {
+            latitude: coord.y,
+            longitude: coord.x,
+        }
+    }
+}
+
+impl From<Coordinate> for Point {
+    fn from(coordinate: Coordinate) -> Self {
+        Point(coordinate.into())
+    }
+}
+
+impl From<Point> for Coordinate {
+    fn from(point: Point) -> Self {
+        point.0.into()
+    }
+}
+
+impl From<BoundingBox> for Rect {
+    /// Convert the box into a `Rect`. A box which crosses the antimeridian keeps its
+    /// northeastern longitude beyond 180.
+    fn from(bounding_box: BoundingBox) -> Self {
+        Rect::new(bounding_box.south_west, bounding_box.north_east)
+    }
+}
+
+impl From<Rect> for BoundingBox {
+    fn from(rect: Rect) -> Self {
+        BoundingBox {
+            south_west: rect.min().into(),
+            north_east: rect.max().into(),
+        }
+    }
+}
+
+impl From<Polygon> for geo_types::Polygon {
+    /// Convert the polygon into a `geo_types::Polygon` without holes, whose ring is closed.
+    fn from(polygon: Polygon) -> Self {
+        let exterior: LineString = polygon.coordinates.into_iter().map(Coord::from).collect();
+        geo_types::Polygon::new(exterior, Vec::new())
+    }
+}
+
+impl From<geo_types::Polygon> for Polygon {
+    /// Convert the exterior of the polygon, without the point which closes the ring, as
+    /// `Polygon::to_string` repeats the first coordinate itself. Holes are left out, as the
+    /// what3words API does not support them.
+    fn from(polygon: geo_types::Polygon) -> Self {
+        let (exterior, _) = polygon.into_inner();
+        let mut coordinates: Vec<Coordinate> = exterior.into_iter().map(Coordinate::from).collect();
+        if coordinates.len() > 1 && coordinates.first() == coordinates.last() {
+            coordinates.pop();
+        }
+        Polygon { coordinates }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geo_types::{point, polygon, Point, Rect};
+
+    use crate::bounding_box::BoundingBox;
+    use crate::coordinate::Coordinate;
+    use crate::polygon::Polygon;
+
+    #[test]
+    fn test_geo_types_round_trip() {
+        let coordinate = Coordinate {
+            latitude: 51.52,
+            longitude: -0.2,
+        };
+        let point: Point = coordinate.into();
+        assert_eq!(point, point!(x: -0.2, y: 51.52));
+        assert_eq!(Coordinate::from(point), coordinate);
+
+        let bounding_box = BoundingBox {
+            south_west: Coordinate {
+                latitude: -21.0,
+                longitude: 177.0,
+            },
+            north_east: Coordinate {
+                latitude: -12.0,
+                longitude: 182.0,
+            },
+        };
+        let rect: Rect = bounding_box.clone().into();
+        assert_eq!(rect.max().x, 182.0);
+        assert_eq!(BoundingBox::from(rect), bounding_box);
+
+        let geo_polygon = polygon![(x: 0.0, y: 51.0), (x: 1.0, y: 51.0), (x: 1.0, y: 52.0)];
+        let polygon = Polygon::from(geo_polygon.clone());
+        assert_eq!(polygon.coordinates.len(), 3);
+        assert_eq!(polygon.coordinates[1].longitude, 1.0);
+        assert_eq!(geo_types::Polygon::from(polygon), geo_polygon);
+    }
+}
@@ -0,0 +1,76 @@
+//! Geodesic helpers shared by the geometry types.
+
+use crate::coordinate::Coordinate;
+
+/// Mean radius of the earth in meters.
+pub(crate) const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// Calculate the great-circle distance in meters between two coordinates with the haversine
+/// formula.
+pub(crate) fn haversine_distance(from: &Coordinate, to: &Coordinate) -> f64 {
+    let lat1 = from.latitude.to_radians();
+    let lat2 = to.latitude.to_radians();
+    let d_lat = (to.latitude - from.latitude).to_radians();
+    let d_lng = (to.longitude - from.longitude).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lng / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+}
+
+/// Calculate the initial bearing in degrees, clockwise from north within 0 and 360, of the
+/// great circle from one coordinate to another.
+pub(crate) fn initial_bearing(from: &Coordinate, to: &Coordinate) -> f64 {
+    let lat1 = from.latitude.to_radians();
+    let lat2 = to.latitude.to_radians();
+    let d_lng = (to.longitude - from.longitude).to_radians();
+    let y = d_lng.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * d_lng.cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
+/// Calculate the point halfway along the great circle between two coordinates.
+pub(crate) fn midpoint(from: &Coordinate, to: &Coordinate) -> Coordinate {
+    let lat1 = from.latitude.to_radians();
+    let lat2 = to.latitude.to_radians();
+    let lng1 = from.longitude.to_radians();
+    let d_lng = (to.longitude - from.longitude).to_radians();
+    let bx = lat2.cos() * d_lng.cos();
+    let by = lat2.cos() * d_lng.sin();
+    let latitude = (lat1.sin() + lat2.sin()).atan2(((lat1.cos() + bx).powi(2) + by.powi(2)).sqrt());
+    let longitude = lng1 + by.atan2(lat1.cos() + bx);
+    Coordinate {
+        latitude: latitude.to_degrees(),
+        longitude: wrap_longitude(longitude.to_degrees()),
+    }
+}
+
+/// Calculate the point reached by travelling `distance_m` meters along the great circle which
+/// starts at `from` with the bearing in degrees clockwise from north.
+pub(crate) fn destination(from: &Coordinate, bearing: f64, distance_m: f64) -> Coordinate {
+    let lat1 = from.latitude.to_radians();
+    let lng1 = from.longitude.to_radians();
+    let bearing = bearing.to_radians();
+    let angle = distance_m / EARTH_RADIUS_M;
+    let lat2 = (lat1.sin() * angle.cos() + lat1.cos() * angle.sin() * bearing.cos()).asin();
+    let lng2 = lng1
+        + (bearing.sin() * angle.sin() * lat1.cos()).atan2(angle.cos() - lat1.sin() * lat2.sin());
+    Coordinate {
+        latitude: lat2.to_degrees(),
+        longitude: wrap_longitude(lng2.to_degrees()),
+    }
+}
+
+/// Wrap a longitude in degrees into the range from -180 up to 180.
+pub(crate) fn wrap_longitude(longitude: f64) -> f64 {
+    (longitude + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Return the ground distance in meters of one degree of latitude.
+pub(crate) fn meters_per_degree_latitude() -> f64 {
+    EARTH_RADIUS_M * 1f64.to_radians()
+}
+
+/// Return the ground distance in meters of one degree of longitude at the given latitude,
+/// which shrinks towards the poles.
+pub(crate) fn meters_per_degree_longitude(latitude: f64) -> f64 {
+    meters_per_degree_latitude() * latitude.to_radians().cos()
+}
@@ -0,0 +1,38 @@
+//! The `Geofence` trait unites the shapes which can tell whether a coordinate lies within them,
+//! so `W3WClient::is_3wa_within` accepts any of them.
+
+use crate::bounding_box::BoundingBox;
+use crate::circle::Circle;
+use crate::coordinate::Coordinate;
+use crate::polygon::Polygon;
+use crate::square::Square;
+
+/// A shape which can tell whether a coordinate lies within it.
+pub trait Geofence {
+    /// Return whether the coordinate lies within the shape.
+    fn contains(&self, coordinate: &Coordinate) -> bool;
+}
+
+impl Geofence for BoundingBox {
+    fn contains(&self, coordinate: &Coordinate) -> bool {
+        BoundingBox::contains(self, coordinate)
+    }
+}
+
+impl Geofence for Circle {
+    fn contains(&self, coordinate: &Coordinate) -> bool {
+        Circle::contains(self, coordinate)
+    }
+}
+
+impl Geofence for Polygon {
+    fn contains(&self, coordinate: &Coordinate) -> bool {
+        Polygon::contains(self, coordinate)
+    }
+}
+
+impl Geofence for Square {
+    fn contains(&self, coordinate: &Coordinate) -> bool {
+        Square::contains(self, coordinate)
+    }
+}
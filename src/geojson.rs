@@ -0,0 +1,226 @@
+//! Conversions between the shapes of this crate and the geometries of the `geojson` crate,
+//! behind the `geojson` feature, so clips can be read from GeoJSON files and results written
+//! back out.
+//!
+//! GeoJSON puts the longitude first in a position. Converting a geometry of the wrong type is
+//! a `W3WError::Validation`.
+
+use ::geojson::{Geometry, PolygonType, Position, Value};
+
+use crate::bounding_box::BoundingBox;
+use crate::coordinate::Coordinate;
+use crate::error::W3WError;
+use crate::polygon::Polygon;
+use crate::square::Square;
+
+impl From<Coordinate> for Geometry {
+    /// Convert the coordinate into a `Point`.
+    fn from(coordinate: Coordinate) -> Self {
+        Geometry::new(Value::Point(position(&coordinate)))
+    }
+}
+
+impl From<Polygon> for Geometry {
+    /// Convert the polygon into a `Polygon` without holes, whose ring is closed.
+    fn from(polygon: Polygon) -> Self {
+        let mut ring: Vec<Position> = polygon.coordinates.iter().map(position).collect();
+        if let Some(first) = ring.first().cloned() {
+            if ring.last() != Some(&first) {
+                ring.push(first);
+            }
+        }
+        Geometry::new(Value::Polygon(vec![ring]))
+    }
+}
+
+impl From<BoundingBox> for Geometry {
+    /// Convert the box into a `Polygon` of its four corners.
+    fn from(bounding_box: BoundingBox) -> Self {
+        bounding_box.to_polygon().into()
+    }
+}
+
+impl From<Square> for Geometry {
+    /// Convert the square into a `Polygon` of its four corners.
+    fn from(square: Square) -> Self {
+        square.to_polygon().into()
+    }
+}
+
+impl TryFrom<Geometry> for Coordinate {
+    type Error = W3WError;
+
+    /// Convert a `Point`.
+    fn try_from(geometry: Geometry) -> Result<Self, Self::Error> {
+        match &geometry.value {
+            Value::Point(point) => coordinate(point),
+            _ => Err(unexpected(&geometry, "Point")),
+        }
+    }
+}
+
+impl TryFrom<Geometry> for Polygon {
+    type Error = W3WError;
+
+    /// Convert the outer ring of a `Polygon`, without the position which closes the ring, as
+    /// `Polygon::to_string` repeats the first coordinate itself. Holes are left out, as the
+    /// what3words API does not support them.
+    fn try_from(geometry: Geometry) -> Result<Self, Self::Error> {
+        let mut coordinates = outer_ring(&geometry)?;
+        if coordinates.len() > 1 && coordinates.first() == coordinates.last() {
+            coordinates.pop();
+        }
+        Ok(Polygon { coordinates })
+    }
+}
+
+impl TryFrom<Geometry> for BoundingBox {
+    type Error = W3WError;
+
+    /// Convert the smallest box around the outer ring of a `Polygon`, which is the box itself
+    /// for the polygon of `From<BoundingBox>`.
+    fn try_from(geometry: Geometry) -> Result<Self, Self::Error> {
+        let coordinates = outer_ring(&geometry)?;
+        if coordinates.is_empty() {
+            return Err(unexpected(&geometry, "Polygon with coordinates"));
+        }
+        let (south, west, north, east) = coordinates.iter().fold(
+            (
+                f64::INFINITY,
+                f64::INFINITY,
+                f64::NEG_INFINITY,
+                f64::NEG_INFINITY,
+            ),
+            |(south, west, north, east), coordinate| {
+                (
+                    south.min(coordinate.latitude),
+                    west.min(coordinate.longitude),
+                    north.max(coordinate.latitude),
+                    east.max(coordinate.longitude),
+                )
+            },
+        );
+        Ok(BoundingBox {
+            south_west: Coordinate {
+                latitude: south,
+                longitude: west,
+            },
+            north_east: Coordinate {
+                latitude: north,
+                longitude: east,
+            },
+        })
+    }
+}
+
+impl TryFrom<Geometry> for Square {
+    type Error = W3WError;
+
+    /// Convert the smallest box around the outer ring of a `Polygon`, like `BoundingBox`.
+    fn try_from(geometry: Geometry) -> Result<Self, Self::Error> {
+        let bounding_box = BoundingBox::try_from(geometry)?;
+        Ok(Square {
+            south_west: bounding_box.south_west,
+            north_east: bounding_box.north_east,
+        })
+    }
+}
+
+/// Return the `[longitude, latitude]` position of a coordinate.
+fn position(coordinate: &Coordinate) -> Position {
+    vec![coordinate.longitude, coordinate.latitude]
+}
+
+/// Return the coordinate of a position, ignoring an altitude.
+fn coordinate(position: &[f64]) -> Result<Coordinate, W3WError> {
+    match position {
+        [longitude, latitude, ..] => Ok(Coordinate {
+            latitude: *latitude,
+            longitude: *longitude,
+        }),
+        _ => Err(W3WError::Validation(format!(
+            "a GeoJSON position needs a longitude and a latitude, got {:?}",
+            position
+        ))),
+    }
+}
+
+/// Return the coordinates of the outer ring of a `Polygon` geometry.
+fn outer_ring(geometry: &Geometry) -> Result<Vec<Coordinate>, W3WError> {
+    let rings: &PolygonType = match &geometry.value {
+        Value::Polygon(rings) => rings,
+        _ => return Err(unexpected(geometry, "Polygon")),
+    };
+    rings
+        .first()
+        .map(|ring| ring.iter().map(|position| coordinate(position)).collect())
+        .unwrap_or_else(|| Ok(Vec::new()))
+}
+
+/// Return the error for a geometry which is not of the `expected` type.
+fn unexpected(geometry: &Geometry, expected: &str) -> W3WError {
+    W3WError::Validation(format!(
+        "expected a GeoJSON {}, got a {}",
+        expected,
+        geometry.value.type_name()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use ::geojson::{Geometry, Value};
+
+    use crate::bounding_box::BoundingBox;
+    use crate::coordinate::Coordinate;
+    use crate::error::W3WError;
+    use crate::polygon::Polygon;
+    use crate::square::Square;
+
+    #[test]
+    fn test_geojson_round_trip() {
+        let coordinate = Coordinate {
+            latitude: 51.52,
+            longitude: -0.2,
+        };
+        let geometry = Geometry::from(coordinate);
+        assert_eq!(geometry.value, Value::Point(vec![-0.2, 51.52]));
+        assert_eq!(Coordinate::try_from(geometry).unwrap(), coordinate);
+
+        let bounding_box = BoundingBox {
+            south_west: Coordinate {
+                latitude: 51.0,
+                longitude: 4.0,
+            },
+            north_east: Coordinate {
+                latitude: 52.0,
+                longitude: 5.0,
+            },
+        };
+        let geometry = Geometry::from(bounding_box.clone());
+        match &geometry.value {
+            Value::Polygon(rings) => assert_eq!(rings[0].len(), 5),
+            other => panic!("expected a polygon, got {:?}", other),
+        }
+        let polygon = Polygon::try_from(geometry.clone()).unwrap();
+        assert_eq!(polygon, bounding_box.to_polygon());
+        assert_eq!(
+            BoundingBox::try_from(geometry.clone()).unwrap(),
+            bounding_box
+        );
+        assert_eq!(
+            Square::try_from(geometry).unwrap().to_bounding_box(),
+            bounding_box
+        );
+
+        let point = Geometry::from(coordinate);
+        assert!(matches!(
+            Polygon::try_from(point),
+            Err(W3WError::Validation(_))
+        ));
+        let line = Geometry::new(Value::LineString(vec![vec![4.0, 51.0], vec![5.0, 52.0]]));
+        assert!(matches!(
+            Coordinate::try_from(line),
+            Err(W3WError::Validation(_))
+        ));
+    }
+}
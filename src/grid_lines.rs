@@ -0,0 +1,257 @@
+//! `GridLines` reads the lines of a grid-section response one at a time. A response for a
+//! bounding box of 4 km holds megabytes of JSON, which this parses without keeping the body
+//! or all of its lines in memory.
+
+use std::io::{BufRead, BufReader, Read};
+
+use serde::de::Deserialize;
+use serde_json::Deserializer;
+
+use crate::error::{DecodeError, W3WError};
+use crate::grid_section::GridLine;
+
+/// An iterator over the lines of a grid-section body in the `"json"` format, which parses every
+/// line as it is read. It stops after the last line or at the first error.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::File::open("grid-section.json")?;
+/// for line in GridLines::new(file) {
+///     let line = line?;
+///     println!("{:?} line of {} m", line.orientation(), line.length_m());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct GridLines<R> {
+    reader: BufReader<R>,
+    state: State,
+    index: usize,
+}
+
+/// Where in the body the reader is.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Before the opening brace of the body
+    Start,
+    /// Within the `lines` array, before the first line
+    First,
+    /// Within the `lines` array, after a line
+    Next,
+    /// After the last line or an error
+    Done,
+}
+
+impl<R: Read> GridLines<R> {
+    /// Creates an iterator over the lines of the grid-section body read from `reader`.
+    pub fn new(reader: R) -> Self {
+        GridLines {
+            reader: BufReader::new(reader),
+            state: State::Start,
+            index: 0,
+        }
+    }
+
+    /// Skip to the start of the `lines` array, passing over any other member of the body.
+    /// Returns whether the body has lines.
+    fn find_lines(&mut self) -> Result<bool, W3WError> {
+        self.expect(b'{', "")?;
+        if self.peek("")? == Some(b'}') {
+            return Ok(false);
+        }
+        loop {
+            let key = String::deserialize(&mut Deserializer::from_reader(&mut self.reader))
+                .map_err(|error| decode_error("", error))?;
+            let path = format!("/{}", key);
+            self.expect(b':', &path)?;
+            if key == "lines" {
+                self.expect(b'[', &path)?;
+                return Ok(true);
+            }
+            self.skip_value(&path)?;
+            match self.next_byte(&path)? {
+                b',' => continue,
+                b'}' => return Ok(false),
+                byte => return Err(unexpected(&path, byte)),
+            }
+        }
+    }
+
+    /// Read the next line of the array, or `None` after its last line.
+    fn next_line(&mut self) -> Result<Option<GridLine>, W3WError> {
+        let path = format!("/lines/{}", self.index);
+        if self.peek(&path)? == Some(b']') {
+            return Ok(None);
+        }
+        if self.state == State::Next {
+            self.expect(b',', &path)?;
+        }
+        let line = GridLine::deserialize(&mut Deserializer::from_reader(&mut self.reader))
+            .map_err(|error| decode_error(&path, error))?;
+        self.index += 1;
+        Ok(Some(line))
+    }
+
+    /// Skip the value of a member which is not `lines`, up to the `,` or `}` after it. The value
+    /// is skipped byte by byte, as a `Deserializer` reads one byte past a number.
+    fn skip_value(&mut self, path: &str) -> Result<(), W3WError> {
+        let (mut depth, mut in_string, mut escaped) = (0usize, false, false);
+        loop {
+            let byte = self.peek_byte(path)?;
+            match byte {
+                _ if in_string => {
+                    in_string = byte != b'"' || escaped;
+                    escaped = byte == b'\\' && !escaped;
+                }
+                b'"' => in_string = true,
+                b'{' | b'[' => depth += 1,
+                b',' | b'}' if depth == 0 => return Ok(()),
+                b'}' | b']' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            self.reader.consume(1);
+        }
+    }
+
+    /// Return the next byte, whitespace included, without consuming it.
+    fn peek_byte(&mut self, path: &str) -> Result<u8, W3WError> {
+        let buffer = self
+            .reader
+            .fill_buf()
+            .map_err(|error| decode_error(path, error))?;
+        buffer.first().copied().ok_or_else(|| end_of_body(path))
+    }
+
+    /// Return the next byte which is not whitespace, without consuming it.
+    fn peek(&mut self, path: &str) -> Result<Option<u8>, W3WError> {
+        loop {
+            let buffer = self
+                .reader
+                .fill_buf()
+                .map_err(|error| decode_error(path, error))?;
+            match buffer.iter().position(|byte| !byte.is_ascii_whitespace()) {
+                Some(position) => {
+                    let byte = buffer[position];
+                    self.reader.consume(position);
+                    return Ok(Some(byte));
+                }
+                None if buffer.is_empty() => return Ok(None),
+                None => {
+                    let length = buffer.len();
+                    self.reader.consume(length);
+                }
+            }
+        }
+    }
+
+    /// Consume the next byte which is not whitespace.
+    fn next_byte(&mut self, path: &str) -> Result<u8, W3WError> {
+        let byte = self.peek(path)?.ok_or_else(|| end_of_body(path))?;
+        self.reader.consume(1);
+        Ok(byte)
+    }
+
+    /// Consume the next byte which is not whitespace, which must be `expected`.
+    fn expect(&mut self, expected: u8, path: &str) -> Result<(), W3WError> {
+        match self.next_byte(path)? {
+            byte if byte == expected => Ok(()),
+            byte => Err(unexpected(path, byte)),
+        }
+    }
+}
+
+impl<R: Read> Iterator for GridLines<R> {
+    type Item = Result<GridLine, W3WError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == State::Start {
+            match self.find_lines() {
+                Ok(true) => self.state = State::First,
+                Ok(false) => self.state = State::Done,
+                Err(error) => {
+                    self.state = State::Done;
+                    return Some(Err(error));
+                }
+            }
+        }
+        if self.state == State::Done {
+            return None;
+        }
+        match self.next_line() {
+            Ok(Some(line)) => {
+                self.state = State::Next;
+                Some(Ok(line))
+            }
+            Ok(None) => {
+                self.state = State::Done;
+                None
+            }
+            Err(error) => {
+                self.state = State::Done;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// Report an error at the JSON pointer `path` as a `W3WError::Decode`.
+fn decode_error(path: &str, error: impl ToString) -> W3WError {
+    W3WError::Decode(DecodeError {
+        path: path.to_string(),
+        message: error.to_string(),
+    })
+}
+
+/// Report that the body ended at the JSON pointer `path`.
+fn end_of_body(path: &str) -> W3WError {
+    decode_error(path, "unexpected end of the body")
+}
+
+/// Report an unexpected byte at the JSON pointer `path`.
+fn unexpected(path: &str, byte: u8) -> W3WError {
+    decode_error(path, format!("unexpected character '{}'", char::from(byte)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GridLines;
+    use crate::error::W3WError;
+
+    const BODY: &str = r#"{
+        "lines": [
+            {"start": {"lat": 51.52, "lng": -0.196}, "end": {"lat": 51.52, "lng": -0.195}},
+            {"start": {"lat": 51.52, "lng": -0.196}, "end": {"lat": 51.53, "lng": -0.196}}
+        ]
+    }"#;
+
+    #[test]
+    fn test_lines_are_read_one_by_one() {
+        let lines: Vec<_> = GridLines::new(BODY.as_bytes())
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].end.latitude, 51.53);
+
+        let other_members = r#"{"count": 2, "note": {"text": "a \"}\" b"}, "lines": []}"#;
+        assert_eq!(GridLines::new(other_members.as_bytes()).count(), 0);
+    }
+
+    #[test]
+    fn test_malformed_lines_name_the_path() {
+        let body = BODY.replacen("-0.195", "\"east\"", 1);
+        let mut lines = GridLines::new(body.as_bytes());
+        match lines.next() {
+            Some(Err(W3WError::Decode(error))) => assert_eq!(error.path, "/lines/0"),
+            other => panic!("expected a decode error, got {:?}", other),
+        }
+        assert!(lines.next().is_none());
+
+        let truncated = &BODY[..BODY.find("},\n").unwrap() + 2];
+        let lines: Vec<_> = GridLines::new(truncated.as_bytes()).collect();
+        assert!(lines[0].is_ok());
+        assert!(matches!(&lines[1], Err(W3WError::Decode(error)) if error.path == "/lines/1"));
+    }
+}
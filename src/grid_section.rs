@@ -0,0 +1,258 @@
+//! Typed representation of the response of the `grid-section` endpoint.
+//! A grid section is a list of lines which together draw the what3words grid within a
+//! `BoundingBox`. `GridSection::squares` turns the lines into the squares between them.
+
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::coordinate::Coordinate;
+use crate::geo_utils::haversine_distance;
+use crate::square::Square;
+
+/// The lines of the what3words grid within a bounding box.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GridSection {
+    /// All lines of the grid section
+    pub lines: Vec<GridLine>,
+}
+
+impl GridSection {
+    /// Return the lines as a GeoJSON `FeatureCollection` with a single `MultiLineString`
+    /// feature, the shape the grid-section endpoint returns for `ResponseFormat::GeoJson`.
+    pub fn to_geojson(&self) -> Value {
+        let lines: Vec<Value> = self
+            .lines
+            .iter()
+            .map(|line| {
+                json!([
+                    [line.start.longitude, line.start.latitude],
+                    [line.end.longitude, line.end.latitude]
+                ])
+            })
+            .collect();
+        json!({
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "geometry": {"type": "MultiLineString", "coordinates": lines},
+                "properties": {}
+            }]
+        })
+    }
+
+    /// Return the squares which are enclosed by the lines, ordered from south to north and then
+    /// from west to east. The squares at the edges of the bounding box, which are cut off by the
+    /// box, are left out.
+    pub fn squares(&self) -> Vec<Square> {
+        self.enclosed_squares().collect()
+    }
+
+    /// Iterate over the centers of the squares which are enclosed by the lines, in the order of
+    /// `GridSection::squares`, e.g. for coverage analysis of a region.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let bounding_box = BoundingBox::new(Coordinate { latitude: 51.521, longitude: -0.196 }, Coordinate { latitude: 51.522, longitude: -0.195 })?;
+    /// # let coverage = Circle::from_meters(Coordinate { latitude: 51.5215, longitude: -0.1955 }, 50.0);
+    /// let grid = w3_client.grid_section_typed(&bounding_box, &GridSectionOptions::default())?;
+    /// let covered = grid.square_centers().filter(|center| coverage.contains(center)).count();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn square_centers(&self) -> impl Iterator<Item = Coordinate> {
+        self.enclosed_squares().map(|square| square.center())
+    }
+
+    /// Iterate over the squares which are enclosed by the lines.
+    fn enclosed_squares(&self) -> impl Iterator<Item = Square> {
+        let mut latitudes = Vec::new();
+        let mut longitudes = Vec::new();
+        for line in &self.lines {
+            match line.orientation() {
+                Orientation::Horizontal => latitudes.push(line.start.latitude),
+                Orientation::Vertical => longitudes.push(line.start.longitude),
+            }
+        }
+        for values in [&mut latitudes, &mut longitudes] {
+            values.sort_by(f64::total_cmp);
+            values.dedup();
+        }
+        let rows = latitudes.len().saturating_sub(1);
+        let columns = longitudes.len().saturating_sub(1);
+        (0..rows * columns).map(move |index| {
+            let (row, column) = (index / columns, index % columns);
+            Square {
+                south_west: Coordinate {
+                    latitude: latitudes[row],
+                    longitude: longitudes[column],
+                },
+                north_east: Coordinate {
+                    latitude: latitudes[row + 1],
+                    longitude: longitudes[column + 1],
+                },
+            }
+        })
+    }
+
+    /// Return the squares enclosed by the lines as a GeoJSON `FeatureCollection` with a
+    /// `Polygon` feature per square.
+    pub fn squares_to_geojson(&self) -> Value {
+        let features: Vec<Value> = self
+            .squares()
+            .iter()
+            .map(|square| {
+                let polygon = square.to_polygon();
+                let mut ring: Vec<Value> = polygon
+                    .coordinates
+                    .iter()
+                    .map(|corner| json!([corner.longitude, corner.latitude]))
+                    .collect();
+                ring.push(ring[0].clone());
+                json!({
+                    "type": "Feature",
+                    "geometry": {"type": "Polygon", "coordinates": [ring]},
+                    "properties": {}
+                })
+            })
+            .collect();
+        json!({"type": "FeatureCollection", "features": features})
+    }
+
+    /// Combine the grid sections of adjacent bounding boxes into one. Lines which are in more
+    /// than one section, like the lines on the border of two boxes, are kept once.
+    pub(crate) fn merge(sections: impl IntoIterator<Item = GridSection>) -> GridSection {
+        let mut seen = HashSet::new();
+        let lines = sections
+            .into_iter()
+            .flat_map(|section| section.lines)
+            .filter(|line| {
+                seen.insert([
+                    line.start.latitude.to_bits(),
+                    line.start.longitude.to_bits(),
+                    line.end.latitude.to_bits(),
+                    line.end.longitude.to_bits(),
+                ])
+            })
+            .collect();
+        GridSection { lines }
+    }
+}
+
+/// A single line of the what3words grid, running from `start` to `end`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct GridLine {
+    /// The coordinate where the line starts
+    pub start: Coordinate,
+    /// The coordinate where the line ends
+    pub end: Coordinate,
+}
+
+/// The direction in which a `GridLine` runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    /// A line along a parallel, running west to east
+    Horizontal,
+    /// A line along a meridian, running south to north
+    Vertical,
+}
+
+impl GridLine {
+    /// Return the geodesic length of the line in meters.
+    pub fn length_m(&self) -> f64 {
+        haversine_distance(&self.start, &self.end)
+    }
+
+    /// Return whether the line runs horizontally or vertically. Grid lines are aligned with
+    /// the parallels and meridians, so the axis with the largest difference wins.
+    pub fn orientation(&self) -> Orientation {
+        let d_lat = (self.end.latitude - self.start.latitude).abs();
+        let d_lng = (self.end.longitude - self.start.longitude).abs();
+        if d_lng >= d_lat {
+            Orientation::Horizontal
+        } else {
+            Orientation::Vertical
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GridLine, GridSection};
+    use crate::coordinate::Coordinate;
+
+    fn line(latitude: f64, longitude: f64) -> GridLine {
+        GridLine {
+            start: Coordinate {
+                latitude,
+                longitude,
+            },
+            end: Coordinate {
+                latitude,
+                longitude: longitude + 0.001,
+            },
+        }
+    }
+
+    fn vertical(longitude: f64, latitude: f64) -> GridLine {
+        GridLine {
+            start: Coordinate {
+                latitude,
+                longitude,
+            },
+            end: Coordinate {
+                latitude: latitude + 0.002,
+                longitude,
+            },
+        }
+    }
+
+    #[test]
+    fn test_squares_between_the_lines() {
+        let grid = GridSection {
+            lines: vec![
+                line(51.0, 4.0),
+                line(51.00003, 4.0),
+                line(51.00006, 4.0),
+                vertical(4.00004, 51.0),
+                vertical(4.00008, 51.0),
+                vertical(4.00004, 51.001),
+            ],
+        };
+        let squares = grid.squares();
+        assert_eq!(squares.len(), 2);
+        assert_eq!(squares[0].south_west.latitude, 51.0);
+        assert_eq!(squares[0].north_east.longitude, 4.00008);
+        assert_eq!(squares[1].south_west.latitude, 51.00003);
+        assert_eq!(squares[1].north_east.latitude, 51.00006);
+
+        let centers: Vec<Coordinate> = grid.square_centers().collect();
+        assert_eq!(centers, [squares[0].center(), squares[1].center()]);
+
+        let geojson = grid.squares_to_geojson();
+        let ring = &geojson["features"][0]["geometry"]["coordinates"][0];
+        assert_eq!(ring.as_array().unwrap().len(), 5);
+        assert_eq!(ring[0], ring[4]);
+    }
+
+    #[test]
+    fn test_merge_drops_shared_lines() {
+        let merged = GridSection::merge([
+            GridSection {
+                lines: vec![line(51.0, 4.0), line(51.1, 4.0)],
+            },
+            GridSection {
+                lines: vec![line(51.1, 4.0), line(51.2, 4.0)],
+            },
+        ]);
+        assert_eq!(
+            merged.lines,
+            [line(51.0, 4.0), line(51.1, 4.0), line(51.2, 4.0)]
+        );
+    }
+}
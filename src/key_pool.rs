@@ -0,0 +1,135 @@
+//! A `KeyPool` spreads the requests of a `W3WClient` over several API keys. Keys are used
+//! round-robin, and a key which runs out of quota is skipped until the pool is reset.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+/// A set of API keys which are used in turn.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::*;
+/// # fn main() -> Result<(), what3words::W3WError> {
+/// let mut w3_client = W3WClient::new("unused");
+/// w3_client.key_pool = Some(KeyPool::new(["first-key", "second-key"]));
+/// # Ok(())
+/// # }
+/// ```
+pub struct KeyPool {
+    keys: Vec<PooledKey>,
+    next: AtomicUsize,
+}
+
+struct PooledKey {
+    key: String,
+    requests: AtomicU64,
+    exhausted: AtomicBool,
+}
+
+/// The usage of a single key of a `KeyPool`. The key itself is left out, so the usage can be
+/// logged safely; keys are identified by their position in the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyUsage {
+    /// The position of the key in the pool
+    pub index: usize,
+    /// The number of requests sent with the key
+    pub requests: u64,
+    /// Whether the key ran out of quota
+    pub exhausted: bool,
+}
+
+impl KeyPool {
+    /// Creates a pool of the given keys, which are used in the given order.
+    pub fn new<K: Into<String>>(keys: impl IntoIterator<Item = K>) -> Self {
+        KeyPool {
+            keys: keys
+                .into_iter()
+                .map(|key| PooledKey {
+                    key: key.into(),
+                    requests: AtomicU64::new(0),
+                    exhausted: AtomicBool::new(false),
+                })
+                .collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Return the position and value of the next key which is not exhausted, and count a
+    /// request for it. Returns `None` when all keys are exhausted.
+    pub(crate) fn next_key(&self) -> Option<(usize, &str)> {
+        for _ in 0..self.keys.len() {
+            let index = self.next.fetch_add(1, Ordering::Relaxed) % self.keys.len();
+            let pooled = &self.keys[index];
+            if !pooled.exhausted.load(Ordering::Relaxed) {
+                pooled.requests.fetch_add(1, Ordering::Relaxed);
+                return Some((index, &pooled.key));
+            }
+        }
+        None
+    }
+
+    /// Mark the key at the given position as out of quota.
+    pub(crate) fn mark_exhausted(&self, index: usize) {
+        self.keys[index].exhausted.store(true, Ordering::Relaxed);
+    }
+
+    /// Make all keys available again, e.g. when a new quota period starts.
+    pub fn reset(&self) {
+        for pooled in &self.keys {
+            pooled.exhausted.store(false, Ordering::Relaxed);
+        }
+    }
+
+    /// Return the usage of every key, in the order of the pool.
+    pub fn usage(&self) -> Vec<KeyUsage> {
+        self.keys
+            .iter()
+            .enumerate()
+            .map(|(index, pooled)| KeyUsage {
+                index,
+                requests: pooled.requests.load(Ordering::Relaxed),
+                exhausted: pooled.exhausted.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for KeyPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("KeyPool")
+            .field("usage", &self.usage())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeyPool, KeyUsage};
+
+    #[test]
+    fn test_keys_rotate_and_skip_exhausted() {
+        let pool = KeyPool::new(["a", "b", "c"]);
+        let keys: Vec<&str> = (0..4).map(|_| pool.next_key().unwrap().1).collect();
+        assert_eq!(keys, ["a", "b", "c", "a"]);
+
+        pool.mark_exhausted(1);
+        let keys: Vec<&str> = (0..3).map(|_| pool.next_key().unwrap().1).collect();
+        assert_eq!(keys, ["c", "a", "c"]);
+        assert_eq!(
+            pool.usage()[1],
+            KeyUsage {
+                index: 1,
+                requests: 1,
+                exhausted: true
+            }
+        );
+
+        pool.mark_exhausted(0);
+        pool.mark_exhausted(2);
+        assert_eq!(pool.next_key(), None);
+        pool.reset();
+        assert!(pool.next_key().is_some());
+        assert!(!format!("{:?}", pool).contains("\"a\""));
+    }
+}
@@ -16,91 +16,464 @@
 
 extern crate reqwest;
 
+mod address;
+mod batch;
 mod bounding_box;
+mod cache;
 mod circle;
 mod coordinate;
+mod coordinate_format;
+mod correction;
+mod country_code;
+mod error;
+mod events;
+#[cfg(feature = "geo")]
+mod geo;
+mod geo_utils;
+mod geofence;
+#[cfg(feature = "geojson")]
+mod geojson;
+mod grid_lines;
+mod grid_section;
+mod key_pool;
+mod location;
 mod options;
+mod plus_code;
 mod polygon;
+mod query;
+mod quota_partition;
+mod rate_limit;
+mod session;
+mod square;
+mod suggestion;
+#[cfg(feature = "testutil")]
+pub mod testutil;
+pub mod text;
+mod three_word_address;
+mod verification;
+pub use address::{Address, AddressChange};
+pub use batch::{BatchAnalysis, BatchItem, BatchResult, Job};
 pub use bounding_box::BoundingBox;
+pub use cache::{AddressCache, AutosuggestCache};
 pub use circle::Circle;
-pub use coordinate::Coordinate;
+pub use coordinate::{Coordinate, CoordinateWarning};
+pub use correction::Correction;
+pub use country_code::CountryCode;
+use error::REDACTED;
+pub use error::{DecodeError, ErrorResponse, W3WError};
+pub use events::{CacheKind, EventListener};
+use geo_utils::haversine_distance;
+pub use geofence::Geofence;
+pub use grid_lines::GridLines;
+pub use grid_section::{GridLine, GridSection, Orientation};
+pub use key_pool::{KeyPool, KeyUsage};
+pub use location::{Location, LocationSource};
+use options::RequestOptions;
 pub use options::{
-    AutoSuggestOptions, ConvertTo3WAOptions, ConvertToCoordinatesOptions, GridSectionOptions,
+    AutoSuggestOptions, ConvertTo3WAOptions, ConvertToCoordinatesOptions, DefaultOptions,
+    GridSectionOptions, InputType, ResponseFormat, SourceApi,
 };
 pub use polygon::Polygon;
-use reqwest::blocking::Response;
+use polygon::MAX_POLYGON_PAIRS;
+use query::{check_distinct_names, query_string};
+pub use quota_partition::{QuotaPartition, TenantUsage};
+pub use rate_limit::RateLimit;
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::header::HeaderMap;
+use reqwest::{StatusCode, Url};
+use serde::de::DeserializeOwned;
 use serde_json::Value;
+pub use session::{AutosuggestSession, JsonlSelectionRecorder, Selection, SelectionRecorder};
+pub use square::{Neighbors, Square};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+pub use suggestion::{merge_suggestions, Suggestion, SuggestionRanker, SuggestionsExt};
+use suggestion::{rerank, Suggestions};
+pub use three_word_address::ThreeWordAddress;
+pub use verification::{Discrepancy, DiscrepancyKind};
 
 const W3WHOST: &str = "https://api.what3words.com/v3";
 
+/// The default of `W3WClient::error_body_limit`.
+const DEFAULT_ERROR_BODY_LIMIT: usize = 4096;
+/// The number of times the bulk calls try an item whose error `is_transient`.
+const BULK_ATTEMPTS: u32 = 3;
+/// The wait before the first retry of a bulk call, which doubles for every next retry.
+const BULK_RETRY_DELAY: Duration = Duration::from_millis(500);
+/// The default of `W3WClient::coordinate_precision`, about 0.1 m.
+const DEFAULT_COORDINATE_PRECISION: u8 = 6;
+/// The default of `W3WClient::quota_max_pauses`.
+const DEFAULT_QUOTA_MAX_PAUSES: u32 = 3;
+
+/// Where the API key is put in the requests to the What3Words API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApiKeyLocation {
+    /// Send the key in the `X-Api-Key` header, which keeps it out of proxy and access logs
+    #[default]
+    Header,
+    /// Send the key as the `key` query parameter, for hosts which do not accept the header
+    Query,
+}
+
+/// The outcome of `W3WClient::validate_api_key`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKeyStatus {
+    /// The key was accepted by the host
+    Valid,
+    /// The host rejected the key, the API responded with `401 Unauthorized`
+    Invalid,
+    /// The key is valid but ran out of quota, the API responded with `402 Payment Required`
+    QuotaExceeded,
+}
+
 /// The main client for interacting with the What3Words API.
-#[derive(Debug)]
+/// Its `Debug` output redacts the API key.
 pub struct W3WClient {
     /// Your W3W API key
     pub api_key: String,
     /// The W3W host which defaults to the what3words API endpoint. This is changeable should you
     /// run a W3W endpoint locally.
     pub host: String,
+    /// Where the API key is put in the requests, which defaults to the `X-Api-Key` header
+    pub api_key_location: ApiKeyLocation,
+    /// Options which apply to every call, unless the options of the call set them
+    pub defaults: DefaultOptions,
+    /// A pool of API keys which are used in turn instead of `api_key`, if set
+    pub key_pool: Option<KeyPool>,
+    /// Extra HTTP headers sent with every request, such as the headers a gateway requires
+    pub default_headers: HeaderMap,
+    /// The number of bytes of the body of an error response kept in the `ErrorResponse`, which
+    /// defaults to 4 KiB. Zero leaves the body out.
+    pub error_body_limit: usize,
+    /// A cache for the results of `autosuggest_typed`, if set
+    pub autosuggest_cache: Option<AutosuggestCache>,
+    /// A cache for the results of `convert_to_3wa_typed`, if set
+    pub address_cache: Option<AddressCache>,
+    /// Whether the coordinate of `convert_to_3wa` and the autosuggest focus are normalized with
+    /// `Coordinate::normalized` before they are sent, which defaults to `false`
+    pub normalize_coordinates: bool,
+    /// Whether the coordinate of `convert_to_3wa` and the autosuggest focus are also rejected
+    /// for a `CoordinateWarning` which is not an error, like `NullIsland`, which defaults to
+    /// `false`
+    pub strict_coordinates: bool,
+    /// The number of decimal places to which the coordinate of `convert_to_3wa` and the
+    /// coordinates of the autosuggest focus and clips are rounded before they are sent, which
+    /// defaults to 6, about 0.1 m. Shorter coordinates make shorter URLs and let nearby inputs
+    /// share cache entries. `None` sends them with full precision.
+    pub coordinate_precision: Option<u8>,
+    /// Keeps the requests within the rate limit of your plan, if set. Every request waits for
+    /// it, including those of the bulk calls and their retries.
+    pub rate_limit: Option<RateLimit>,
+    /// How long the bulk calls pause when the API key runs out of quota, after which they
+    /// reset the `key_pool` and resume, if set. By default the remaining items fail with the
+    /// quota error instead.
+    pub quota_pause: Option<Duration>,
+    /// How many times a bulk call pauses for `quota_pause` before the remaining items fail
+    /// with the quota error, which defaults to 3
+    pub quota_max_pauses: u32,
+    /// Re-orders the suggestions of the typed autosuggest calls, if set
+    pub suggestion_ranker: Option<Box<dyn SuggestionRanker>>,
+    /// Is told about cache hits and misses, retries and quota, if set
+    pub event_listener: Option<Box<dyn EventListener>>,
     /// The API client
     pub client: reqwest::blocking::Client,
 }
 
+impl fmt::Debug for W3WClient {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("W3WClient")
+            .field("api_key", &REDACTED)
+            .field("host", &self.host)
+            .field("api_key_location", &self.api_key_location)
+            .field("defaults", &self.defaults)
+            .field("key_pool", &self.key_pool)
+            .field("default_headers", &self.default_headers)
+            .field("error_body_limit", &self.error_body_limit)
+            .field("autosuggest_cache", &self.autosuggest_cache)
+            .field("address_cache", &self.address_cache)
+            .field("normalize_coordinates", &self.normalize_coordinates)
+            .field("strict_coordinates", &self.strict_coordinates)
+            .field("coordinate_precision", &self.coordinate_precision)
+            .field("rate_limit", &self.rate_limit)
+            .field("quota_pause", &self.quota_pause)
+            .field("quota_max_pauses", &self.quota_max_pauses)
+            .field(
+                "suggestion_ranker",
+                &self.suggestion_ranker.as_ref().map(|_| "SuggestionRanker"),
+            )
+            .field(
+                "event_listener",
+                &self.event_listener.as_ref().map(|_| "EventListener"),
+            )
+            .field("client", &self.client)
+            .finish()
+    }
+}
+
 impl W3WClient {
     /// Creates a new instance of the What3Words client with the provided API key.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
     /// let w3_client = W3WClient::new("your_api_key");
+    /// # Ok(())
+    /// # }
     /// ```
     pub fn new(api_key: &str) -> Self {
         Self {
             api_key: api_key.to_string(),
             host: W3WHOST.to_string(),
+            api_key_location: ApiKeyLocation::default(),
+            defaults: DefaultOptions::default(),
+            key_pool: None,
+            default_headers: HeaderMap::new(),
+            error_body_limit: DEFAULT_ERROR_BODY_LIMIT,
+            autosuggest_cache: None,
+            address_cache: None,
+            normalize_coordinates: false,
+            strict_coordinates: false,
+            coordinate_precision: Some(DEFAULT_COORDINATE_PRECISION),
+            rate_limit: None,
+            quota_pause: None,
+            quota_max_pauses: DEFAULT_QUOTA_MAX_PAUSES,
+            suggestion_ranker: None,
+            event_listener: None,
             client: reqwest::blocking::Client::new(),
         }
     }
 
-    /// Executes a GET request to the given url
-    fn get_request(&self, url: String) -> Result<Response, Response> {
-        let resp = self.client.get(url).send();
-        let mut response = resp.unwrap();
-        response = check_status_code(response)?;
+    /// Creates a new instance of the What3Words client configured through environment variables:
+    ///
+    /// - `W3W_API_KEY`: your W3W API key, which is required;
+    /// - `W3W_HOST`: the W3W host, which defaults to the what3words API endpoint;
+    /// - `W3W_LANGUAGE`: the default language of every call;
+    /// - `W3W_TIMEOUT_SECS`: the timeout of every request in seconds.
+    ///
+    /// A missing API key or an invalid timeout is reported as a validation error.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// let w3_client = W3WClient::from_env()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_env() -> Result<Self, W3WError> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Creates a new client from the variables returned by `var`, see `from_env`.
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, W3WError> {
+        let api_key = var("W3W_API_KEY").ok_or_else(|| {
+            W3WError::Validation(String::from("the W3W_API_KEY variable is not set"))
+        })?;
+        let mut w3_client = W3WClient::new(&api_key);
+        if let Some(host) = var("W3W_HOST") {
+            w3_client.host = host;
+        }
+        if let Some(language) = var("W3W_LANGUAGE") {
+            w3_client.defaults.language = Some(language);
+        }
+        if let Some(timeout) = var("W3W_TIMEOUT_SECS") {
+            let seconds: u64 = timeout.parse().map_err(|_| {
+                W3WError::Validation(format!(
+                    "W3W_TIMEOUT_SECS must be a whole number of seconds, got '{}'",
+                    timeout
+                ))
+            })?;
+            w3_client.client = reqwest::blocking::Client::builder()
+                .timeout(Duration::from_secs(seconds))
+                .build()?;
+        }
+        Ok(w3_client)
+    }
+
+    /// Builds the URL of the given endpoint on top of `host`. A trailing slash in `host` is
+    /// allowed, but a host which is not a valid base URL is reported as a validation error.
+    fn endpoint_url(&self, endpoint: &str) -> Result<Url, W3WError> {
+        let mut url = Url::parse(&self.host)
+            .map_err(|e| W3WError::Validation(format!("invalid host '{}': {}", self.host, e)))?;
+        if url.query().is_some() || url.fragment().is_some() {
+            return Err(W3WError::Validation(format!(
+                "invalid host '{}': a host cannot contain a query or fragment",
+                self.host
+            )));
+        }
+        url.path_segments_mut()
+            .map_err(|_| {
+                W3WError::Validation(format!("invalid host '{}': not a base URL", self.host))
+            })?
+            .pop_if_empty()
+            .push(endpoint);
+        Ok(url)
+    }
+
+    /// Builds a GET request to the given endpoint with the API key, the required `params` and
+    /// the optional parameters in `options` as query string. The API key is added to the
+    /// headers or the query string depending on `api_key_location`. A parameter which is given
+    /// more than once, or options which cannot be serialized as query parameters, are reported
+    /// as a validation error.
+    fn build_request<O: RequestOptions>(
+        &self,
+        endpoint: &str,
+        params: &[(&str, &str)],
+        options: &O,
+    ) -> Result<RequestBuilder, W3WError> {
+        self.build_request_with_key(&self.api_key, endpoint, params, options)
+    }
+
+    /// Builds a GET request like `build_request`, but with the given API key.
+    fn build_request_with_key<O: RequestOptions>(
+        &self,
+        api_key: &str,
+        endpoint: &str,
+        params: &[(&str, &str)],
+        options: &O,
+    ) -> Result<RequestBuilder, W3WError> {
+        let mut request = self
+            .client
+            .get(self.endpoint_url(endpoint)?)
+            .headers(self.default_headers.clone());
+        if let Some(headers) = options.headers() {
+            request = request.headers(headers.clone());
+        }
+        match self.api_key_location {
+            ApiKeyLocation::Header => request = request.header("X-Api-Key", api_key),
+            ApiKeyLocation::Query => {
+                check_distinct_names(&[("key", api_key)], options)?;
+                request = request.query(&[("key", api_key)]);
+            }
+        }
+        check_distinct_names(params, options)?;
+        Ok(request.query(params).query(options))
+    }
+
+    /// Executes a GET request to the given endpoint, after waiting for the `rate_limit`. With a
+    /// `key_pool`, a key which runs out of quota is marked as exhausted and the request is
+    /// retried with the next key. When no key is left, it fails with a `402 Payment Required`
+    /// error like a single key would.
+    fn get_request<O: RequestOptions>(
+        &self,
+        endpoint: &str,
+        params: &[(&str, &str)],
+        options: &O,
+    ) -> Result<Response, W3WError> {
+        let key_pool = match &self.key_pool {
+            Some(key_pool) => key_pool,
+            None => {
+                self.wait_for_rate_limit();
+                let response = self.build_request(endpoint, params, options)?.send()?;
+                return self.check_status_code(response);
+            }
+        };
+        let mut last_error = None;
+        while let Some((index, api_key)) = key_pool.next_key() {
+            self.wait_for_rate_limit();
+            let response = self
+                .build_request_with_key(api_key, endpoint, params, options)?
+                .send()?;
+            if response.status() != StatusCode::PAYMENT_REQUIRED {
+                return self.check_status_code(response);
+            }
+            key_pool.mark_exhausted(index);
+            self.emit(|listener| {
+                let keys_left = key_pool.usage().iter().filter(|key| !key.exhausted).count();
+                listener.quota_low(keys_left)
+            });
+            last_error = Some(self.check_status_code(response));
+        }
+        match last_error {
+            Some(last_error) => last_error,
+            None => Err(W3WError::Response(ErrorResponse {
+                status: StatusCode::PAYMENT_REQUIRED,
+                url: self.endpoint_url(endpoint)?.into(),
+                body: String::from("all keys of the key pool are exhausted"),
+            })),
+        }
+    }
+
+    /// Return the `QuotaGate` of a bulk call, which pauses for `quota_pause` at most
+    /// `quota_max_pauses` times.
+    fn quota_gate(&self) -> QuotaGate<'_> {
+        QuotaGate {
+            pause: self.quota_pause,
+            max_pauses: self.quota_max_pauses,
+            key_pool: self.key_pool.as_ref(),
+            events: self.event_listener.as_deref(),
+            state: Mutex::new(PauseState {
+                pauses: 0,
+                resume_at: None,
+            }),
+        }
+    }
+
+    /// Tell the `event_listener`, if any, about an event.
+    fn emit(&self, event: impl FnOnce(&dyn EventListener)) {
+        if let Some(listener) = &self.event_listener {
+            event(listener.as_ref());
+        }
+    }
+
+    /// Block until the `rate_limit`, if any, allows another request.
+    fn wait_for_rate_limit(&self) {
+        if let Some(rate_limit) = &self.rate_limit {
+            rate_limit.acquire();
+        }
+    }
+
+    /// Check the status code of a response. If the status code is between 400 and 599, an
+    /// error will be printed to io::stderr and the response is read into an `ErrorResponse`.
+    fn check_status_code(&self, response: Response) -> Result<Response, W3WError> {
+        let status_code = response.status();
+        if status_code.is_client_error() || status_code.is_server_error() {
+            eprintln!(
+                "The response returned an error, status code: {}",
+                status_code
+            );
+            let response = ErrorResponse::read(response, self.error_body_limit);
+            return Err(W3WError::Response(response));
+        }
         Ok(response)
     }
 
-    /// Converts a coordinate to a 3word address.
+    /// Converts a coordinate to a 3word address. A coordinate which is NaN, out of range or
+    /// swapped is reported as a validation error without sending a request, see
+    /// `Coordinate::warning`. The coordinate may also be a `(latitude, longitude)` tuple or
+    /// array, like the `convert_to_3wa` calls below.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
     /// let coordinate = Coordinate {
     ///     latitude: 50.01,
     ///     longitude: 4.53234
-    /// }
+    /// };
     /// let resp = w3_client.convert_to_3wa(&coordinate, &ConvertTo3WAOptions::default());
+    /// let resp = w3_client.convert_to_3wa((50.01, 4.53234), &ConvertTo3WAOptions::default());
+    /// # Ok(())
+    /// # }
     /// ```
     pub fn convert_to_3wa(
         &self,
-        coordinates: &Coordinate,
+        coordinates: impl Into<Coordinate>,
         options: &ConvertTo3WAOptions,
-    ) -> Result<Response, Response> {
-        let mut url = format!(
-            "{}/convert-to-3wa?key={}&coordinates={}",
-            self.host,
-            self.api_key,
-            coordinates.to_string(),
-        );
-        if let Some(language) = options.language {
-            url = parse_url(url, "language", language);
-        }
-        if let Some(format) = options.format {
-            url = parse_url(url, "format", format);
-        }
-        if let Some(locale) = options.locale {
-            url = parse_url(url, "locale", locale);
-        }
-        let resp = self.get_request(url)?;
+    ) -> Result<Response, W3WError> {
+        let coordinates = self.outgoing_coordinate(&coordinates.into())?.to_string();
+        let params = [("coordinates", coordinates.as_str())];
+        let options = options.or_defaults(&self.defaults);
+        let resp = self.get_request("convert-to-3wa", &params, &options)?;
         Ok(resp)
     }
 
@@ -108,80 +481,243 @@ impl W3WClient {
     ///
     /// # Examples
     ///
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
     /// let coordinate = Coordinate {
     ///     latitude: 50.0012,
     ///     longitude: -3.23
-    /// }
+    /// };
     /// let resp_json = w3_client.convert_to_3wa_json(&coordinate, &ConvertTo3WAOptions::default());
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// Different options can be added to the call:
     ///
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let coordinate = Coordinate { latitude: 50.0012, longitude: -3.23 };
     /// let options = ConvertTo3WAOptions {
     ///     language: Some("nl"),
     ///     ..Default::default()
-    /// }
+    /// };
     /// let resp_json = w3_client.convert_to_3wa_json(&coordinate, &options);
+    /// # Ok(())
+    /// # }
     /// ```
     pub fn convert_to_3wa_json(
         &self,
-        coordinates: &Coordinate,
+        coordinates: impl Into<Coordinate>,
         options: &ConvertTo3WAOptions,
-    ) -> Result<Value, Response> {
+    ) -> Result<Value, W3WError> {
         let resp = self.convert_to_3wa(coordinates, options);
         let json = get_json(resp)?;
         Ok(json)
     }
 
-    /// Convert a coordinate to a 3word address and return the string.
+    /// Convert a coordinate to a 3word address and return the string. The words are read from
+    /// the payload matching the requested `format`; a payload without them is reported as a
+    /// `W3WError::Decode`.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
     /// let coordinate = Coordinate {
     ///     latitude: 50.0012,
     ///     longitude: -3.23
-    /// }
+    /// };
     /// let resp_string = w3_client.convert_to_3wa_string(&coordinate,
-    /// ConvertTo3WAOptions::default());
+    /// &ConvertTo3WAOptions::default());
+    /// # Ok(())
+    /// # }
     /// ```
     pub fn convert_to_3wa_string(
         &self,
-        coordinates: &Coordinate,
+        coordinates: impl Into<Coordinate>,
         options: &ConvertTo3WAOptions,
-    ) -> Result<String, Response> {
+    ) -> Result<String, W3WError> {
         let json = self.convert_to_3wa_json(coordinates, options)?;
-        let result = json["words"].to_string();
-        Ok(result)
+        let path = match options.or_defaults(&self.defaults).format {
+            Some(ResponseFormat::GeoJson) => "/features/0/properties/words",
+            _ => "/words",
+        };
+        let words = extract_str(&json, path)?;
+        Ok(words.to_string())
+    }
+
+    /// Converts a coordinate to a 3word address and parses the response into a typed `Address`.
+    /// The address is always requested in the `"json"` format. With an `address_cache`, a
+    /// coordinate within the square of a cached address with the same options is answered from
+    /// the cache.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let coordinate = Coordinate { latitude: 51.520847, longitude: -0.195521 };
+    /// let address = w3_client.convert_to_3wa_typed(&coordinate, &ConvertTo3WAOptions::default())?;
+    /// println!("{} near {}", address.words, address.nearest_place);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn convert_to_3wa_typed(
+        &self,
+        coordinates: impl Into<Coordinate>,
+        options: &ConvertTo3WAOptions,
+    ) -> Result<Address, W3WError> {
+        let coordinates = coordinates.into();
+        let options = ConvertTo3WAOptions {
+            format: Some(ResponseFormat::Json),
+            ..*options
+        };
+        let cache_key = match &self.address_cache {
+            Some(cache) => {
+                let key = address_cache_key(&options.or_defaults(&self.defaults))?;
+                if let Some(address) = cache.get(&key, &coordinates) {
+                    self.emit(|listener| listener.cache_hit(CacheKind::Address));
+                    return Ok(address);
+                }
+                self.emit(|listener| listener.cache_miss(CacheKind::Address));
+                Some((cache, key))
+            }
+            None => None,
+        };
+        let address: Address = decode(self.convert_to_3wa(coordinates, &options)?)?;
+        if let Some((cache, key)) = cache_key {
+            cache.insert(key, address.clone());
+        }
+        Ok(address)
+    }
+
+    /// Convert a 3word address to coordinates and return whether they lie within `shape`, which
+    /// is a `BoundingBox`, `Circle`, `Polygon` or `Square`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let depot = Coordinate { latitude: 51.520847, longitude: -0.195521 };
+    /// let delivery_zone = Circle {
+    ///     centerpoint: depot,
+    ///     radius: 5.0,
+    /// };
+    /// if !w3_client.is_3wa_within("filled.count.soap", &delivery_zone)? {
+    ///     println!("we do not deliver there");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_3wa_within(
+        &self,
+        three_words: impl AsRef<str>,
+        shape: &impl Geofence,
+    ) -> Result<bool, W3WError> {
+        let coordinate = self.convert_to_coordinates_and_get_coordinate(
+            three_words,
+            &ConvertToCoordinatesOptions::default(),
+        )?;
+        Ok(shape.contains(&coordinate))
+    }
+
+    /// Fetch the addresses of the four squares which share an edge with `square`, e.g. for
+    /// "delivery is one square east". Set an `address_cache` on the client to save the calls
+    /// for squares whose address is already known, like the neighbors of a neighbor.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let options = ConvertToCoordinatesOptions::default();
+    /// let address = w3_client.convert_to_coordinates_typed("filled.count.soap", &options)?;
+    /// let neighbors = w3_client.neighbors(&address.square, &ConvertTo3WAOptions::default())?;
+    /// println!("one square east is {}", neighbors.east.words);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn neighbors(
+        &self,
+        square: &Square,
+        options: &ConvertTo3WAOptions,
+    ) -> Result<Neighbors<Address>, W3WError> {
+        let centers = square.neighbor_centers();
+        Ok(Neighbors {
+            north: self.convert_to_3wa_typed(centers.north, options)?,
+            east: self.convert_to_3wa_typed(centers.east, options)?,
+            south: self.convert_to_3wa_typed(centers.south, options)?,
+            west: self.convert_to_3wa_typed(centers.west, options)?,
+        })
     }
 
-    /// Convert a 3word address to a coordinate.
+    /// Return the what3words square which contains the coordinate, with its `center` and
+    /// corners, e.g. to snap a GPS position to the grid. Set an `address_cache` on the client
+    /// to answer nearby positions, like the jitter of a GPS receiver, from the cache: any
+    /// cached address whose square contains the coordinate is used, whatever its language.
     ///
     /// # Example
     ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let mut w3_client = W3WClient::new("your_api_key");
+    /// # let position = Coordinate { latitude: 51.520847, longitude: -0.195521 };
+    /// w3_client.address_cache = Some(AddressCache::new(1000));
+    /// let square = w3_client.snap_to_square(position)?;
+    /// println!("snapped to {}", square.center());
+    /// # Ok(())
+    /// # }
     /// ```
+    pub fn snap_to_square(&self, coordinate: impl Into<Coordinate>) -> Result<Square, W3WError> {
+        let coordinate = coordinate.into();
+        if let Some(square) = self
+            .address_cache
+            .as_ref()
+            .and_then(|cache| cache.get_square(&coordinate))
+        {
+            self.emit(|listener| listener.cache_hit(CacheKind::Address));
+            return Ok(square);
+        }
+        let address = self.convert_to_3wa_typed(coordinate, &ConvertTo3WAOptions::default())?;
+        Ok(address.square)
+    }
+
+    /// Convert a 3word address to a coordinate. The address can be given as a `&str`, a `String`
+    /// or a `ThreeWordAddress`. It is normalized first, so input like `///fight.offer.airbag` or
+    /// `https://w3w.co/fight.offer.airbag` is accepted as well.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
     /// let three_word_address = "fight.offer.airbag";
     /// let resp = w3_client.convert_to_coordinates(three_word_address,
-    /// ConvertToCoordinatesOptions::default());
+    /// &ConvertToCoordinatesOptions::default());
+    /// # Ok(())
+    /// # }
     /// ```
     pub fn convert_to_coordinates(
         &self,
-        three_words: &str,
+        three_words: impl AsRef<str>,
         options: &ConvertToCoordinatesOptions,
-    ) -> Result<Response, Response> {
-        let mut url = format!(
-            "{}/convert-to-coordinates?words={}&key={}",
-            self.host, three_words, self.api_key
-        );
-        if let Some(format) = options.format {
-            url = parse_url(url, "format", format);
-        }
-        if let Some(locale) = options.locale {
-            url = parse_url(url, "locale", locale);
-        }
-        let resp = self.get_request(url)?;
+    ) -> Result<Response, W3WError> {
+        let words = ThreeWordAddress::normalize(three_words.as_ref());
+        let params = [("words", words.as_str())];
+        let options = options.or_defaults(&self.defaults);
+        let resp = self.get_request("convert-to-coordinates", &params, &options)?;
         Ok(resp)
     }
 
@@ -189,94 +725,385 @@ impl W3WClient {
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
     /// let three_word_address = "fight.offer.airbag";
     /// let options = ConvertToCoordinatesOptions {
-    ///     format: Some("geojson"),
+    ///     format: Some(ResponseFormat::GeoJson),
     ///     ..Default::default()
-    /// }
+    /// };
     /// let resp_json = w3_client.convert_to_coordinates_json(three_word_address, &options)?;
+    /// # Ok(())
+    /// # }
     /// ```
     pub fn convert_to_coordinates_json(
         &self,
-        three_words: &str,
+        three_words: impl AsRef<str>,
         options: &ConvertToCoordinatesOptions,
-    ) -> Result<Value, Response> {
+    ) -> Result<Value, W3WError> {
         let resp = self.convert_to_coordinates(three_words, options);
         let json = get_json(resp)?;
         Ok(json)
     }
 
-    /// Convert a 3word address to a coordinate and fetch the latitude and longitude.
+    /// Convert a 3word address to a coordinate and fetch the latitude and longitude. The
+    /// coordinate is read from the payload matching the requested `format`.
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
     /// let three_word_address = "fight.offer.airbag";
     /// let resp_coordinate = w3_client.convert_to_coordinates_and_get_coordinate(three_word_address,
-    /// ConvertToCoordinatesOptions::default());
+    /// &ConvertToCoordinatesOptions::default());
+    /// # Ok(())
+    /// # }
     /// ```
     pub fn convert_to_coordinates_and_get_coordinate(
         &self,
-        three_words: &str,
+        three_words: impl AsRef<str>,
         options: &ConvertToCoordinatesOptions,
-    ) -> Result<Coordinate, Response> {
+    ) -> Result<Coordinate, W3WError> {
         let three_words_json: Value = self.convert_to_coordinates_json(three_words, options)?;
-        let latitude: f64 = three_words_json["coordinates"]["lat"]
-            .as_f64()
-            .expect("Failed to parse JSON latitude to f64");
-        let longitude: f64 = three_words_json["coordinates"]["lng"]
-            .as_f64()
-            .expect("Failed to parse JSON longitude to f64");
+        let (latitude_path, longitude_path) = match options.or_defaults(&self.defaults).format {
+            Some(ResponseFormat::GeoJson) => (
+                "/features/0/geometry/coordinates/1",
+                "/features/0/geometry/coordinates/0",
+            ),
+            _ => ("/coordinates/lat", "/coordinates/lng"),
+        };
         Ok(Coordinate {
-            latitude,
-            longitude,
+            latitude: extract_f64(&three_words_json, latitude_path)?,
+            longitude: extract_f64(&three_words_json, longitude_path)?,
+        })
+    }
+
+    /// Convert a 3word address to a coordinate and parse the response into a typed `Address`.
+    /// The address is always requested in the `"json"` format. With an `address_cache`, an
+    /// address which is already cached is answered from the cache, and the fetched address is
+    /// cached for `convert_to_3wa_typed` in its language.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// let address = w3_client.convert_to_coordinates_typed("fight.offer.airbag",
+    /// &ConvertToCoordinatesOptions::default())?;
+    /// println!("{} is at {}", address.words, address.coordinates);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn convert_to_coordinates_typed(
+        &self,
+        three_words: impl AsRef<str>,
+        options: &ConvertToCoordinatesOptions,
+    ) -> Result<Address, W3WError> {
+        let options = ConvertToCoordinatesOptions {
+            format: Some(ResponseFormat::Json),
+            ..*options
+        };
+        if let Some(cache) = &self.address_cache {
+            let words = ThreeWordAddress::normalize(three_words.as_ref()).to_lowercase();
+            let locale = options.or_defaults(&self.defaults).locale;
+            if let Some(address) = cache.get_by_words(&words, locale) {
+                self.emit(|listener| listener.cache_hit(CacheKind::Address));
+                return Ok(address);
+            }
+            self.emit(|listener| listener.cache_miss(CacheKind::Address));
+        }
+        let address: Address = decode(self.convert_to_coordinates(three_words, &options)?)?;
+        if let Some(cache) = &self.address_cache {
+            let key = address_cache_key(&ConvertTo3WAOptions {
+                language: Some(&address.language),
+                format: Some(ResponseFormat::Json),
+                locale: address.locale.as_deref(),
+                headers: None,
+            })?;
+            cache.insert(key, address.clone());
+        }
+        Ok(address)
+    }
+
+    /// Convert two 3word addresses to coordinates and return the distance between them in
+    /// meters. Both addresses are converted at the same time, and answered from the
+    /// `address_cache` if set.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// let distance_m = w3_client.distance_between_3wa("filled.count.soap", "index.home.raft")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn distance_between_3wa(
+        &self,
+        a: impl AsRef<str>,
+        b: impl AsRef<str>,
+    ) -> Result<f64, W3WError> {
+        let options = ConvertToCoordinatesOptions::default();
+        let coordinates = run_concurrently(&[a.as_ref(), b.as_ref()], 2, |words| {
+            Ok(self
+                .convert_to_coordinates_typed(words, &options)?
+                .coordinates)
         })
+        .into_iter()
+        .collect::<Result<Vec<Coordinate>, W3WError>>()?;
+        Ok(coordinates[0].distance_to(&coordinates[1]))
+    }
+
+    /// Re-resolve stored three word addresses and report the records which no longer verify.
+    /// Each record is a tuple of an identifier, the stored three word address and the stored
+    /// coordinate. A record is reported when its address now resolves further than
+    /// `tolerance_m` meters from the stored coordinate, or when it cannot be resolved anymore.
+    /// The records are resolved one after the other, in order.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// let records = vec![(1, "filled.count.soap", Coordinate { latitude: 51.520847, longitude: -0.195521 })];
+    /// for discrepancy in w3_client.verify_addresses(records, 5.0) {
+    ///     println!("record {} ({}): {:?}", discrepancy.id, discrepancy.words, discrepancy.kind);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn verify_addresses<I, W: AsRef<str>>(
+        &self,
+        records: impl IntoIterator<Item = (I, W, Coordinate)>,
+        tolerance_m: f64,
+    ) -> Vec<Discrepancy<I>> {
+        let options = ConvertToCoordinatesOptions::default();
+        let mut discrepancies = Vec::new();
+        for (id, words, expected) in records {
+            let kind = match self.convert_to_coordinates_and_get_coordinate(&words, &options) {
+                Ok(actual) => {
+                    let distance_m = haversine_distance(&expected, &actual);
+                    if distance_m <= tolerance_m {
+                        continue;
+                    }
+                    DiscrepancyKind::Drifted {
+                        expected,
+                        actual,
+                        distance_m,
+                    }
+                }
+                Err(error) => DiscrepancyKind::Failed(error),
+            };
+            discrepancies.push(Discrepancy {
+                id,
+                words: words.as_ref().to_string(),
+                kind,
+            });
+        }
+        discrepancies
+    }
+
+    /// Check whether user input is a three word address, and suggest corrections if it is not.
+    /// The input is checked locally first: input which does not look like a three word
+    /// address, not even with the wrong separators, is `NotAnAddress` without calling the API.
+    /// Otherwise the separators are fixed and the input is sent to autosuggest.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// match w3_client.suggest_correction("filled, count, soap")? {
+    ///     Correction::Valid(words) => println!("{} is valid", words),
+    ///     Correction::Candidates(suggestions) => println!("did you mean {}?", suggestions[0].words),
+    ///     Correction::NotAnAddress => println!("not a 3word address"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn suggest_correction(&self, input: &str) -> Result<Correction, W3WError> {
+        let normalized = ThreeWordAddress::normalize(input);
+        let (words, as_is) = if text::is_possible_3wa(&normalized) {
+            (normalized.to_lowercase(), true)
+        } else {
+            match text::fix_separators(input.trim()) {
+                Some(words) => (words.to_lowercase(), false),
+                None => return Ok(Correction::NotAnAddress),
+            }
+        };
+        let suggestions = self.autosuggest_typed(&words, &AutoSuggestOptions::default())?;
+        if as_is
+            && suggestions
+                .iter()
+                .any(|suggestion| suggestion.words == words)
+        {
+            Ok(Correction::Valid(words))
+        } else if suggestions.is_empty() {
+            Ok(Correction::NotAnAddress)
+        } else {
+            Ok(Correction::Candidates(suggestions))
+        }
+    }
+
+    /// Resolve any kind of location input: a `latitude,longitude` pair, a coordinate in degrees,
+    /// minutes and seconds or in the ISO 6709 format, or a full plus code is decoded offline, a
+    /// three word address is converted to coordinates, and any other text is resolved to the
+    /// best autosuggest suggestion. Returns `None` when autosuggest has no suggestions for the
+    /// text.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// if let Some(location) = w3_client.resolve("///filled.count.soap")? {
+    ///     println!("{} ({:?})", location.coordinate, location.source);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resolve(&self, input: &str) -> Result<Option<Location>, W3WError> {
+        if let Ok(coordinate) = input.parse::<Coordinate>() {
+            coordinate.validate()?;
+            return Ok(Some(Location {
+                coordinate,
+                words: None,
+                source: LocationSource::Coordinates,
+            }));
+        }
+        if let Ok(coordinate) =
+            Coordinate::from_dms(input).or_else(|_| Coordinate::from_iso6709(input))
+        {
+            return Ok(Some(Location {
+                coordinate,
+                words: None,
+                source: LocationSource::Coordinates,
+            }));
+        }
+        if let Some(coordinate) = plus_code::decode(input) {
+            return Ok(Some(Location {
+                coordinate,
+                words: None,
+                source: LocationSource::PlusCode,
+            }));
+        }
+        let (words, source) = if text::is_possible_3wa(&ThreeWordAddress::normalize(input)) {
+            (input.to_string(), LocationSource::ThreeWordAddress)
+        } else {
+            let suggestions = self.autosuggest_typed(input, &AutoSuggestOptions::default())?;
+            match suggestions.into_iter().next() {
+                Some(suggestion) => (suggestion.words, LocationSource::Autosuggest),
+                None => return Ok(None),
+            }
+        };
+        let address =
+            self.convert_to_coordinates_typed(words, &ConvertToCoordinatesOptions::default())?;
+        Ok(Some(Location {
+            coordinate: address.coordinates,
+            words: Some(address.words),
+            source,
+        }))
     }
 
     /// Get all available languages and locales.
     ///
     /// # Examples
     ///
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
     /// let languages_resp = w3_client.available_languages();
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn available_languages(&self) -> Result<Response, Response> {
-        let url = format!("{}/available-languages?key={}", self.host, self.api_key);
-        let resp = self.get_request(url);
-        resp
+    pub fn available_languages(&self) -> Result<Response, W3WError> {
+        self.get_request("available-languages", &[], &())
     }
 
-    /// Get all available languages and locales response JSON body.
+    /// Check whether the configured host accepts the configured API key, by calling the cheap
+    /// `available-languages` endpoint. Meant for startup self-checks, so services can fail fast
+    /// on a wrong key or host. Any other error, like an unreachable host, is returned as is.
     ///
     /// # Example
     ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// match w3_client.validate_api_key()? {
+    ///     ApiKeyStatus::Valid => println!("ready"),
+    ///     status => panic!("unusable what3words key: {:?}", status),
+    /// }
+    /// # Ok(())
+    /// # }
     /// ```
+    pub fn validate_api_key(&self) -> Result<ApiKeyStatus, W3WError> {
+        match self.available_languages() {
+            Ok(_) => Ok(ApiKeyStatus::Valid),
+            Err(W3WError::Response(response)) if response.status == StatusCode::UNAUTHORIZED => {
+                Ok(ApiKeyStatus::Invalid)
+            }
+            Err(error) if error.is_quota_exceeded() => Ok(ApiKeyStatus::QuotaExceeded),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Get all available languages and locales response JSON body.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
     /// let languages_resp = w3_client.available_languages_json();
+    /// # Ok(())
+    /// # }
     /// ```
-    pub fn available_languages_json(&self) -> Result<Value, Response> {
+    pub fn available_languages_json(&self) -> Result<Value, W3WError> {
         let resp = self.available_languages();
         let json = get_json(resp)?;
         Ok(json)
     }
 
-    /// Autosuggest 3word addresses based on provided parameters.
+    /// Autosuggest 3word addresses based on provided parameters. The input can be given as a
+    /// `&str`, a `String` or a `ThreeWordAddress`. It is normalized first, so `///fight.offer.ai`
+    /// and `fight offer ai` are sent as `fight.offer.ai`.
     ///
     /// # Examples
     ///
     /// ## No extra options
     ///
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
     /// let incomplete_three_words: &str = "fight.offer.ai";
     /// let autosuggest_resp = w3_client.autosuggest(incomplete_three_words,
     /// &AutoSuggestOptions::default());
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// ## Focus coordinates
     ///
     /// Get autosuggstions in order, based on the provided focus point.
     ///
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let incomplete_three_words: &str = "fight.offer.ai";
     /// let coordinates = Coordinate{
     ///     latitude: 51.0,
     ///     longitude: 4.0
@@ -286,19 +1113,25 @@ impl W3WClient {
     ///     ..Default::default()
     /// };
     /// let autosuggest_resp = w3_client.autosuggest(incomplete_three_words, &options);
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// ## Circle
     ///
     /// Get autosuggestions within a given circle.
     ///
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let incomplete_three_words: &str = "fight.offer.ai";
     /// let coordinates = Coordinate{
     ///     latitude: 51.0,
     ///     longitude: 4.0
     /// };
     /// let circle = Circle {
-    ///     centerpoint: &coordinates,
+    ///     centerpoint: coordinates,
     ///     radius: 35.0
     /// };
     /// let options = AutoSuggestOptions {
@@ -306,6 +1139,8 @@ impl W3WClient {
     ///     ..Default::default()
     /// };
     /// let autosuggest_resp = w3_client.autosuggest(incomplete_three_words, &options);
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// ## Countries
@@ -313,17 +1148,24 @@ impl W3WClient {
     /// Restricts AutoSuggest to only return results inside the countries specified by
     /// comma-separated list of uppercase ISO 3166-1 alpha-2 country codes
     /// (for example, to restrict to Belgium and the UK, use clip-to-country=GB,BE).
-    /// Clip-to-country will also accept lowercase country codes. Entries must be two a-z letters.
+    /// Lowercase country codes are uppercased before they are sent. An entry which is not two
+    /// ASCII letters is reported as a `W3WError::Validation` instead of returning no results.
     /// WARNING: If the two-letter code does not correspond to a country, there is no error:
     /// API simply returns no results.
     ///
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let incomplete_three_words: &str = "fight.offer.ai";
     /// let countries = vec!["GB", "BE"];
     /// let options = AutoSuggestOptions {
     ///     countries: Some(&countries),
     ///     ..Default::default()
     /// };
     /// let resp = w3_client.autosuggest_json(incomplete_three_words, &options);
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// ## BoundingBox
@@ -335,7 +1177,11 @@ impl W3WClient {
     /// Lng is allowed to wrap, so that you can specify bounding boxes which cross
     /// the ante-meridian: -4,178.2,22,195.4
     ///
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let incomplete_three_words: &str = "fight.offer.ai";
     /// let coordinate_sw = Coordinate {
     ///     latitude: -4.0,
     ///     longitude: 178.2
@@ -345,14 +1191,16 @@ impl W3WClient {
     ///     longitude: 195.4
     /// };
     /// let bounding_box = BoundingBox {
-    ///     south_west: &coordinate_sw,
-    ///     north_east: &coordinate_ne
+    ///     south_west: coordinate_sw,
+    ///     north_east: coordinate_ne
     /// };
     /// let options = AutoSuggestOptions {
     ///     bounding_box: Some(&bounding_box),
     ///     ..Default::default()
     /// };
     /// let resp = w3_client.autosuggest_json(incomplete_three_words, &options);
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// ## Polygon
@@ -360,7 +1208,11 @@ impl W3WClient {
     /// Restrict AutoSuggest results to a polygon, specified by a comma-separated list of lat,lng pairs.
     /// The API is currently limited to accepting up to 25 pairs.
     ///
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let incomplete_three_words: &str = "fight.offer.ai";
     /// let coordinates1 = Coordinate {
     ///     latitude: 51.521,
     ///     longitude: -0.343,
@@ -374,192 +1226,2612 @@ impl W3WClient {
     ///     longitude: 8.343,
     /// };
     /// let polygon: Polygon = Polygon {
-    ///     coordinates: vec![&coordinates1, &coordinates2, &coordinates3],
+    ///     coordinates: vec![coordinates1, coordinates2, coordinates3],
     /// };
     /// let options = AutoSuggestOptions {
     ///     polygon: Some(&polygon),
     ///     ..Default::default()
     /// };
     /// let resp = w3_client.autosuggest_json(incomplete_three_words, &options);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Number of results
+    ///
+    /// Request up to 100 suggestions with `n_results`, of which the first `n_focus_results` are
+    /// sorted by distance to the focus. Values outside this range are a validation error.
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let incomplete_three_words: &str = "fight.offer.ai";
+    /// # let focus = Coordinate { latitude: 51.0, longitude: 4.0 };
+    /// let options = AutoSuggestOptions {
+    ///     focus_coordinates: Some(&focus),
+    ///     n_results: Some(10),
+    ///     n_focus_results: Some(5),
+    ///     ..Default::default()
+    /// };
+    /// let resp = w3_client.autosuggest_json(incomplete_three_words, &options);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Voice input
+    ///
+    /// The output of a speech recognition is passed on as is by setting the `input_type`, which
+    /// requires a `language`. Only `InputType::Text` input is normalized.
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// let options = AutoSuggestOptions {
+    ///     input_type: Some(InputType::GenericVoice),
+    ///     language: Some("en"),
+    ///     ..Default::default()
+    /// };
+    /// let resp = w3_client.autosuggest_json("filled count soap", &options);
+    /// # Ok(())
+    /// # }
     /// ```
     pub fn autosuggest(
         &self,
-        input: &str,
+        input: impl AsRef<str>,
         options: &AutoSuggestOptions,
-    ) -> Result<Response, Response> {
-        let mut url = format!(
-            "{}/autosuggest?key={}&input={}",
-            self.host, self.api_key, input
-        );
-        if let Some(focus_coordinates) = options.focus_coordinates {
-            url = parse_url(url, "focus", &focus_coordinates.to_string());
-        }
-        if let Some(circle) = options.circle {
-            url = parse_url(url, "clip-to-circle", &circle.to_string());
-        }
-        if let Some(country_value) = &options.countries {
-            let mut countries: String = String::new();
-            for country in country_value.iter() {
-                countries.push_str(&format!("{},", &country));
-            }
-            countries.pop();
-            url = parse_url(url, "clip-to-country", &countries);
-        }
-        if let Some(bounding_box) = options.bounding_box {
-            url = parse_url(url, "clip-to-bounding-box", &bounding_box.to_string());
-        }
-        if let Some(polygon) = options.polygon {
-            url = parse_url(url, "clip-to-polygon", &polygon.to_string());
-        }
-        if let Some(language) = options.language {
-            url = parse_url(url, "language", language);
-        }
-        if let Some(prefer_land) = options.prefer_land {
-            url = parse_url(url, "prefer-land", &format!("{}", prefer_land));
-        }
-        if let Some(locale) = options.locale {
-            url = parse_url(url, "locale", locale);
-        }
-        let resp = self.get_request(url)?;
-        Ok(resp)
+    ) -> Result<Response, W3WError> {
+        self.autosuggest_request("autosuggest", input.as_ref(), options)
     }
 
     /// Autosuggest 3word addresses based on provided parameters and fetch the JSON body.
-    /// ```
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
     /// let incomplete_three_words: &str = "fight.offer.ai";
     /// let autosuggest_resp = w3_client.autosuggest_json(incomplete_three_words,
-    /// AutoSuggestOptions::default());
+    /// &AutoSuggestOptions::default());
+    /// # Ok(())
+    /// # }
     /// ```
     pub fn autosuggest_json(
         &self,
-        input: &str,
+        input: impl AsRef<str>,
         options: &AutoSuggestOptions,
-    ) -> Result<Value, Response> {
+    ) -> Result<Value, W3WError> {
         let resp = self.autosuggest(input, options);
         let json = get_json(resp)?;
         Ok(json)
     }
 
-    /// Retrieve a list of the coordinates of all what3words squares in a given rectangle
-    /// which is defined by the coordinates of the southwestern and norteastern points.
+    /// Autosuggest 3word addresses and return the suggestions as typed `Suggestion`s. With an
+    /// `autosuggest_cache`, a call with the same input and options is answered from the cache.
+    /// With a `suggestion_ranker`, the suggestions are re-ordered by its scores, and keep the
+    /// `rank` the API gave them.
     ///
     /// # Example
     ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let options = AutoSuggestOptions::default();
+    /// for (index, suggestion) in w3_client.autosuggest_typed("filled.count.so", &options)?.iter().enumerate() {
+    ///     println!("{}. {} near {}", index + 1, suggestion.words, suggestion.nearest_place);
+    /// }
+    /// # Ok(())
+    /// # }
     /// ```
-    /// let coordinate_sw = Coordinate {
-    ///     latitude: -4.0,
-    ///     longitude: 178.2
-    /// };
-    /// let coordinate_ne = Coordinate {
-    ///     latitude: 22.0,
-    ///     longitude: 195.4
-    /// };
-    /// let bounding_box = BoundingBox {
-    ///     south_west: coordinate_sw,
-    ///     north_east: coordinate_ne
-    /// };
-    /// let resp = w3_client.grid_section(&bounding_box, GridSectionOptions::default());
-    /// ```
-    pub fn grid_section(
+    pub fn autosuggest_typed(
         &self,
-        bounding_box: &BoundingBox,
-        options: &GridSectionOptions,
-    ) -> Result<Response, Response> {
-        let mut url = format!(
-            "{}/grid-section?bounding-box={}&key={}",
-            self.host,
-            bounding_box.to_string(),
-            self.api_key
-        );
-        if let Some(format) = options.format {
-            url = parse_url(url, "format", format);
-        }
-        let resp = self.get_request(url)?;
-        Ok(resp)
+        input: impl AsRef<str>,
+        options: &AutoSuggestOptions,
+    ) -> Result<Vec<Suggestion>, W3WError> {
+        self.with_autosuggest_params(input.as_ref(), options, |params, options| {
+            let cache_key = match &self.autosuggest_cache {
+                Some(cache) => {
+                    let key = query_string(params, options)?;
+                    if let Some(suggestions) = cache.get(&key) {
+                        self.emit(|listener| listener.cache_hit(CacheKind::Autosuggest));
+                        return Ok(self.rank_suggestions(suggestions));
+                    }
+                    self.emit(|listener| listener.cache_miss(CacheKind::Autosuggest));
+                    Some((cache, key))
+                }
+                None => None,
+            };
+            let suggestions: Suggestions =
+                decode(self.get_request("autosuggest", params, options)?)?;
+            if let Some((cache, key)) = cache_key {
+                cache.insert(key, suggestions.suggestions.clone());
+            }
+            Ok(self.rank_suggestions(suggestions.suggestions))
+        })
     }
 
-    /// Fetch the JSON body of the `grid_section` call.
+    /// Autosuggest 3word addresses like `autosuggest`, but with the square and coordinates of
+    /// every suggestion in the response. This saves a `convert_to_coordinates` call per
+    /// suggestion, but requires a plan which includes the `autosuggest-with-coordinates`
+    /// endpoint.
     ///
     /// # Example
     ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// let resp = w3_client.autosuggest_with_coordinates("filled.count.so", &AutoSuggestOptions::default());
+    /// # Ok(())
+    /// # }
     /// ```
-    /// let coordinate_sw = Coordinate {
-    ///     latitude: -4.0,
-    ///     longitude: 178.2
-    /// };
-    /// let coordinate_ne = Coordinate {
-    ///     latitude: 22.0,
-    ///     longitude: 195.4
-    /// };
-    /// let bounding_box = BoundingBox {
-    ///     south_west: coordinate_sw,
-    ///     north_east: coordinate_ne
-    /// };
-    /// let resp_json = w3_client.grid_section_json(&bounding_box, GridSectionOptions::default());
-    /// ```
-    pub fn grid_section_json(
+    pub fn autosuggest_with_coordinates(
         &self,
-        bounding_box: &BoundingBox,
-        options: &GridSectionOptions,
-    ) -> Result<Value, Response> {
-        let resp = self.grid_section(bounding_box, options);
-        let json = get_json(resp)?;
+        input: impl AsRef<str>,
+        options: &AutoSuggestOptions,
+    ) -> Result<Response, W3WError> {
+        self.autosuggest_request("autosuggest-with-coordinates", input.as_ref(), options)
+    }
+
+    /// Call `autosuggest_with_coordinates` and fetch the JSON body.
+    pub fn autosuggest_with_coordinates_json(
+        &self,
+        input: impl AsRef<str>,
+        options: &AutoSuggestOptions,
+    ) -> Result<Value, W3WError> {
+        let resp = self.autosuggest_with_coordinates(input, options);
+        let json = get_json(resp)?;
+        Ok(json)
+    }
+
+    /// Call `autosuggest_with_coordinates` and return the suggestions as typed `Suggestion`s,
+    /// which have their `square`, `coordinates` and `map` set. With a `suggestion_ranker`, the
+    /// suggestions are re-ordered by its scores, and keep the `rank` the API gave them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let options = AutoSuggestOptions::default();
+    /// for suggestion in w3_client.autosuggest_with_coordinates_typed("filled.count.so", &options)? {
+    ///     println!("{} at {:?}", suggestion.words, suggestion.coordinates);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn autosuggest_with_coordinates_typed(
+        &self,
+        input: impl AsRef<str>,
+        options: &AutoSuggestOptions,
+    ) -> Result<Vec<Suggestion>, W3WError> {
+        let suggestions: Suggestions = decode(self.autosuggest_with_coordinates(input, options)?)?;
+        Ok(self.rank_suggestions(suggestions.suggestions))
+    }
+
+    /// Re-order the suggestions with the `suggestion_ranker`, if set.
+    fn rank_suggestions(&self, suggestions: Vec<Suggestion>) -> Vec<Suggestion> {
+        match &self.suggestion_ranker {
+            Some(ranker) => rerank(ranker.as_ref(), suggestions),
+            None => suggestions,
+        }
+    }
+
+    /// Fetch the coordinates of the first `n` suggestions, e.g. to show them on a map. At most
+    /// `concurrency` `convert_to_coordinates` calls are in flight at once; suggestions which
+    /// already have coordinates, from `autosuggest_with_coordinates`, are not fetched again.
+    /// The suggestions are returned in their original order, or the first error if a call
+    /// failed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let options = AutoSuggestOptions::default();
+    /// let suggestions = w3_client.autosuggest_typed("filled.count.so", &options)?;
+    /// for (suggestion, coordinate) in w3_client.resolve_suggestions(&suggestions, 3, 3)? {
+    ///     println!("{} at {}", suggestion.words, coordinate);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resolve_suggestions(
+        &self,
+        suggestions: &[Suggestion],
+        n: usize,
+        concurrency: usize,
+    ) -> Result<Vec<(Suggestion, Coordinate)>, W3WError> {
+        let suggestions = &suggestions[..n.min(suggestions.len())];
+        let options = ConvertToCoordinatesOptions::default();
+        let resolve = |suggestion: &Suggestion| match suggestion.coordinates {
+            Some(coordinate) => Ok(coordinate),
+            None => self.convert_to_coordinates_and_get_coordinate(&suggestion.words, &options),
+        };
+        run_concurrently(suggestions, concurrency, resolve)
+            .into_iter()
+            .zip(suggestions)
+            .map(|(coordinate, suggestion)| Ok((suggestion.clone(), coordinate?)))
+            .collect()
+    }
+
+    /// Report which suggestion the user selected, as what3words asks integrators to do. The
+    /// `raw_input` is the input the suggestions were made for, `selection` the selected 3word
+    /// address and `rank` its rank in the suggestions.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let suggestions = w3_client.autosuggest_typed("filled.count.so", &AutoSuggestOptions::default())?;
+    /// let suggestion = &suggestions[0];
+    /// w3_client.autosuggest_selection("filled.count.so", &suggestion.words, suggestion.rank, SourceApi::Text)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn autosuggest_selection(
+        &self,
+        raw_input: &str,
+        selection: impl AsRef<str>,
+        rank: u32,
+        source_api: SourceApi,
+    ) -> Result<(), W3WError> {
+        let rank = rank.to_string();
+        let params = [
+            ("raw-input", raw_input),
+            ("selection", selection.as_ref()),
+            ("rank", rank.as_str()),
+            ("source-api", source_api.as_str()),
+        ];
+        self.get_request("autosuggest-selection", &params, &())?;
+        Ok(())
+    }
+
+    /// Executes a request to one of the autosuggest endpoints, which share their parameters.
+    fn autosuggest_request(
+        &self,
+        endpoint: &str,
+        input: &str,
+        options: &AutoSuggestOptions,
+    ) -> Result<Response, W3WError> {
+        self.with_autosuggest_params(input, options, |params, options| {
+            self.get_request(endpoint, params, options)
+        })
+    }
+
+    /// Merge the options with the client defaults, check them and normalize the input, then
+    /// hand the resulting parameters and options to `request`.
+    fn with_autosuggest_params<T>(
+        &self,
+        input: &str,
+        options: &AutoSuggestOptions,
+        request: impl FnOnce(&[(&str, &str)], &AutoSuggestOptions) -> Result<T, W3WError>,
+    ) -> Result<T, W3WError> {
+        let default_countries = self.defaults.country_list();
+        let options = options.or_defaults(&self.defaults, default_countries.as_ref());
+        let circle = options.circle.map(|circle| Circle {
+            centerpoint: self.round_coordinate(&circle.centerpoint),
+            radius: circle.radius,
+        });
+        let bounding_box = options.bounding_box.map(|bounding_box| BoundingBox {
+            south_west: self.round_coordinate(&bounding_box.south_west),
+            north_east: self.round_coordinate(&bounding_box.north_east),
+        });
+        let polygon = options.polygon.map(|polygon| Polygon {
+            coordinates: polygon
+                .coordinates
+                .iter()
+                .map(|coordinate| self.round_coordinate(coordinate))
+                .collect(),
+        });
+        let polygon = polygon.map(|polygon| match polygon.normalized() {
+            polygon if options.simplify_polygon && polygon.exceeds_limit() => {
+                polygon.simplified(MAX_POLYGON_PAIRS - 1)
+            }
+            polygon => polygon,
+        });
+        let options = AutoSuggestOptions {
+            circle: circle.as_ref(),
+            bounding_box: bounding_box.as_ref(),
+            polygon: polygon.as_ref(),
+            ..options
+        };
+        options.validate()?;
+        let input = if options.is_voice() {
+            input.to_string()
+        } else {
+            ThreeWordAddress::normalize(input)
+        };
+        let params = [("input", input.as_str())];
+        let focus_coordinates = options
+            .focus_coordinates
+            .map(|focus_coordinates| self.outgoing_coordinate(focus_coordinates))
+            .transpose()?;
+        let options = AutoSuggestOptions {
+            focus_coordinates: focus_coordinates.as_ref(),
+            ..options
+        };
+        request(&params, &options)
+    }
+
+    /// Check a coordinate before it is sent, and normalize it if `normalize_coordinates` is set.
+    /// With `strict_coordinates` every `CoordinateWarning` is rejected. The coordinate is
+    /// rounded to the `coordinate_precision`.
+    fn outgoing_coordinate(&self, coordinate: &Coordinate) -> Result<Coordinate, W3WError> {
+        let coordinate = if self.normalize_coordinates {
+            coordinate.normalized()?
+        } else {
+            coordinate.validate()?;
+            *coordinate
+        };
+        if self.strict_coordinates {
+            coordinate.validate_warnings(|_| true)?;
+        }
+        Ok(self.round_coordinate(&coordinate))
+    }
+
+    /// Round a coordinate to the `coordinate_precision`, if set.
+    fn round_coordinate(&self, coordinate: &Coordinate) -> Coordinate {
+        match self.coordinate_precision {
+            Some(decimals) => coordinate.rounded(decimals),
+            None => *coordinate,
+        }
+    }
+
+    /// Retrieve a list of the coordinates of all what3words squares in a given rectangle
+    /// which is defined by the coordinates of the southwestern and norteastern points. The box
+    /// may be at most 4 km from corner to corner; a larger box is rejected with a
+    /// `W3WError::Validation` before any request is sent.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// let coordinate_sw = Coordinate {
+    ///     latitude: -4.0,
+    ///     longitude: 178.2
+    /// };
+    /// let coordinate_ne = Coordinate {
+    ///     latitude: 22.0,
+    ///     longitude: 195.4
+    /// };
+    /// let bounding_box = BoundingBox {
+    ///     south_west: coordinate_sw,
+    ///     north_east: coordinate_ne
+    /// };
+    /// let resp = w3_client.grid_section(&bounding_box, &GridSectionOptions::default());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn grid_section(
+        &self,
+        bounding_box: &BoundingBox,
+        options: &GridSectionOptions,
+    ) -> Result<Response, W3WError> {
+        bounding_box.validate()?;
+        bounding_box.validate_grid_section()?;
+        let bounding_box = bounding_box.to_string();
+        let params = [("bounding-box", bounding_box.as_str())];
+        let options = options.or_defaults(&self.defaults);
+        let resp = self.get_request("grid-section", &params, &options)?;
+        Ok(resp)
+    }
+
+    /// Fetch the JSON body of the `grid_section` call.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// let coordinate_sw = Coordinate {
+    ///     latitude: -4.0,
+    ///     longitude: 178.2
+    /// };
+    /// let coordinate_ne = Coordinate {
+    ///     latitude: 22.0,
+    ///     longitude: 195.4
+    /// };
+    /// let bounding_box = BoundingBox {
+    ///     south_west: coordinate_sw,
+    ///     north_east: coordinate_ne
+    /// };
+    /// let resp_json = w3_client.grid_section_json(&bounding_box, &GridSectionOptions::default());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn grid_section_json(
+        &self,
+        bounding_box: &BoundingBox,
+        options: &GridSectionOptions,
+    ) -> Result<Value, W3WError> {
+        let resp = self.grid_section(bounding_box, options);
+        let json = get_json(resp)?;
         Ok(json)
     }
+
+    /// Fetch the lines of the `grid_section` call as a typed `GridSection`. The lines are
+    /// always requested in the `"json"` format, and parsed with `GridLines` while the body is
+    /// read, so the body is never held in memory as a whole.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let bounding_box = BoundingBox::new((51.521, -0.343), (51.523, -0.341))?;
+    /// let grid = w3_client.grid_section_typed(&bounding_box, &GridSectionOptions::default())?;
+    /// for line in grid.lines {
+    ///     println!("{:?} line of {} m", line.orientation(), line.length_m());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn grid_section_typed(
+        &self,
+        bounding_box: &BoundingBox,
+        options: &GridSectionOptions,
+    ) -> Result<GridSection, W3WError> {
+        let options = GridSectionOptions {
+            format: Some(ResponseFormat::Json),
+            ..*options
+        };
+        let lines =
+            GridLines::new(self.grid_section(bounding_box, &options)?).collect::<Result<_, _>>()?;
+        Ok(GridSection { lines })
+    }
+
+    /// Iterate over the lines of the grid section of a bounding box of any size. Like
+    /// `grid_section_tiled`, a box over the 4 km limit is split into tiles, but the tiles are
+    /// fetched one after the other, only when the lines of the previous tile have been read, and
+    /// every line is parsed as the body is read. This keeps the memory use flat, e.g. while
+    /// writing out the lines of a whole city. Lines are split at the borders of the tiles, and
+    /// a line which lies on the border of two tiles is returned for both. The iterator stops
+    /// after the first error, and a box which needs more than 1000 tiles only returns a
+    /// `W3WError::Validation`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::io::Write;
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let city = BoundingBox::new((51.50, -0.13), (51.52, -0.10))?;
+    /// # let mut out = std::io::stdout();
+    /// for line in w3_client.grid_section_lines(&city, &GridSectionOptions::default()) {
+    ///     let line = line?;
+    ///     writeln!(out, "{},{}", line.start, line.end)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn grid_section_lines<'a>(
+        &'a self,
+        bounding_box: &BoundingBox,
+        options: &GridSectionOptions<'a>,
+    ) -> impl Iterator<Item = Result<GridLine, W3WError>> + 'a {
+        let options = GridSectionOptions {
+            format: Some(ResponseFormat::Json),
+            ..*options
+        };
+        let (tiles, error) = match bounding_box.grid_section_tiles() {
+            Ok(tiles) => (tiles, None),
+            Err(error) => (Vec::new(), Some(Err(error))),
+        };
+        let mut failed = false;
+        error
+            .into_iter()
+            .chain(tiles.into_iter().flat_map(
+                move |tile| -> Box<dyn Iterator<Item = Result<GridLine, W3WError>>> {
+                    match self.grid_section(&tile, &options) {
+                        Ok(resp) => Box::new(GridLines::new(resp)),
+                        Err(error) => Box::new(std::iter::once(Err(error))),
+                    }
+                },
+            ))
+            .take_while(move |line| {
+                let take = !failed;
+                failed |= line.is_err();
+                take
+            })
+    }
+
+    /// Fetch the grid section of a bounding box of any size, like a whole city, as a single
+    /// `GridSection`. The box is split into tiles which each fit the 4 km limit of
+    /// `grid_section`, and the tiles are fetched with at most `concurrency` calls in flight,
+    /// which wait for the `rate_limit` of the client. Lines are split at the borders of the
+    /// tiles. Returns the first error if a call failed. A box which crosses the antimeridian is
+    /// tiled eastward, and a box which needs more than 1000 tiles, one call each, is rejected
+    /// with a `W3WError::Validation` which names the number of tiles.
+    ///
+    /// Use `GridSection::to_geojson` for the GeoJSON of the merged lines.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let city = BoundingBox::new((51.50, -0.13), (51.52, -0.10))?;
+    /// let grid = w3_client.grid_section_tiled(&city, &GridSectionOptions::default(), 4)?;
+    /// std::fs::write("grid.geojson", grid.to_geojson().to_string())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn grid_section_tiled(
+        &self,
+        bounding_box: &BoundingBox,
+        options: &GridSectionOptions,
+        concurrency: usize,
+    ) -> Result<GridSection, W3WError> {
+        let tiles = bounding_box.grid_section_tiles()?;
+        let sections = run_concurrently(&tiles, concurrency, |tile| {
+            self.grid_section_typed(tile, options)
+        })
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+        Ok(GridSection::merge(sections))
+    }
+
+    /// Convert many coordinates to 3word addresses, e.g. a whole table of points, with at most
+    /// `concurrency` calls in flight. The calls wait for the `rate_limit` of the client, to stay
+    /// within the rate limit of your plan. A call which fails with an error which
+    /// `is_transient`, like `429 Too Many Requests`, is tried up to 3 times. Returns the result
+    /// of every coordinate in the order of `coordinates`, so one failed coordinate does not
+    /// lose the others. With an `address_cache`, coordinates within an already converted square
+    /// are not fetched again. Pair the results with `coordinates` in a `BatchResult` to count
+    /// and split them.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let points = vec![Coordinate { latitude: 51.520847, longitude: -0.195521 }];
+    /// let results = w3_client.convert_to_3wa_bulk(
+    ///     &points,
+    ///     &ConvertTo3WAOptions::default(),
+    ///     8,
+    /// );
+    /// for (point, result) in points.iter().zip(results) {
+    ///     match result {
+    ///         Ok(address) => println!("{} is {}", point, address.words),
+    ///         Err(error) => eprintln!("{} failed: {}", point, error),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn convert_to_3wa_bulk(
+        &self,
+        coordinates: &[Coordinate],
+        options: &ConvertTo3WAOptions,
+        concurrency: usize,
+    ) -> Vec<Result<Address, W3WError>> {
+        self.convert_to_3wa_bulk_with_progress(coordinates, options, concurrency, |_, _, _| {})
+    }
+
+    /// Convert many coordinates to 3word addresses like `convert_to_3wa_bulk`, calling
+    /// `on_progress(done, total, result)` after every coordinate, e.g. to update a progress bar
+    /// or emit a heartbeat. `on_progress` is called from the worker threads, in the order in
+    /// which the coordinates finish.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let points = vec![Coordinate { latitude: 51.520847, longitude: -0.195521 }];
+    /// let results = w3_client.convert_to_3wa_bulk_with_progress(
+    ///     &points,
+    ///     &ConvertTo3WAOptions::default(),
+    ///     8,
+    ///     |done, total, _| eprint!("\r{}/{}", done, total),
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn convert_to_3wa_bulk_with_progress(
+        &self,
+        coordinates: &[Coordinate],
+        options: &ConvertTo3WAOptions,
+        concurrency: usize,
+        on_progress: impl Fn(usize, usize, &Result<Address, W3WError>) + Sync,
+    ) -> Vec<Result<Address, W3WError>> {
+        run_bulk(
+            &self.quota_gate(),
+            coordinates,
+            concurrency,
+            |coordinate| self.convert_to_3wa_typed(coordinate, options),
+            on_progress,
+        )
+    }
+
+    /// Convert many 3word addresses to coordinates, the reverse of `convert_to_3wa_bulk`, with
+    /// the same concurrency, rate limit and retries. Returns the result of every address in
+    /// the order of `three_words`. With an `address_cache`, addresses which were converted
+    /// before are not fetched again.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// let coordinates = w3_client.convert_to_coordinates_bulk(
+    ///     ["filled.count.soap", "index.home.raft"],
+    ///     &ConvertToCoordinatesOptions::default(),
+    ///     8,
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn convert_to_coordinates_bulk(
+        &self,
+        three_words: impl IntoIterator<Item = impl AsRef<str>>,
+        options: &ConvertToCoordinatesOptions,
+        concurrency: usize,
+    ) -> Vec<Result<Coordinate, W3WError>> {
+        self.convert_to_coordinates_bulk_with_progress(
+            three_words,
+            options,
+            concurrency,
+            |_, _, _| {},
+        )
+    }
+
+    /// Convert many 3word addresses to coordinates like `convert_to_coordinates_bulk`, calling
+    /// `on_progress(done, total, result)` after every address, like
+    /// `convert_to_3wa_bulk_with_progress`.
+    pub fn convert_to_coordinates_bulk_with_progress(
+        &self,
+        three_words: impl IntoIterator<Item = impl AsRef<str>>,
+        options: &ConvertToCoordinatesOptions,
+        concurrency: usize,
+        on_progress: impl Fn(usize, usize, &Result<Coordinate, W3WError>) + Sync,
+    ) -> Vec<Result<Coordinate, W3WError>> {
+        let three_words: Vec<String> = three_words
+            .into_iter()
+            .map(|words| words.as_ref().to_string())
+            .collect();
+        run_bulk(
+            &self.quota_gate(),
+            &three_words,
+            concurrency,
+            |words| {
+                self.convert_to_coordinates_typed(words, options)
+                    .map(|address| address.coordinates)
+            },
+            on_progress,
+        )
+    }
+
+    /// Enrich `records` with the coordinates of their 3word addresses, e.g. the rows of a
+    /// customer file. `words` returns the address of a record and `set_coordinates` stores the
+    /// coordinates in it. The addresses are converted with `convert_to_coordinates_bulk`, so
+    /// with the same concurrency, rate limit, retries and `address_cache`.
+    ///
+    /// Returns every record with the result of its address, in the order of `records`. Only
+    /// the records of which the address was converted have their coordinates set.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// struct Customer {
+    ///     words: String,
+    ///     location: Option<Coordinate>,
+    /// }
+    ///
+    /// # let customers: Vec<Customer> = Vec::new();
+    /// let joined = w3_client.join_coordinates(
+    ///     customers,
+    ///     |customer| customer.words.as_str(),
+    ///     |customer, coordinates| customer.location = Some(coordinates),
+    ///     &ConvertToCoordinatesOptions::default(),
+    ///     8,
+    /// );
+    /// for (item, error) in joined.failures() {
+    ///     eprintln!("{} could not be located: {}", item.input.words, error);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn join_coordinates<R>(
+        &self,
+        records: impl IntoIterator<Item = R>,
+        words: impl Fn(&R) -> &str,
+        mut set_coordinates: impl FnMut(&mut R, Coordinate),
+        options: &ConvertToCoordinatesOptions,
+        concurrency: usize,
+    ) -> BatchResult<R, Coordinate> {
+        let records: Vec<R> = records.into_iter().collect();
+        let results =
+            self.convert_to_coordinates_bulk(records.iter().map(words), options, concurrency);
+        let mut joined = BatchResult::new(records, results);
+        for item in &mut joined.items {
+            if let Ok(coordinates) = item.result {
+                set_coordinates(&mut item.input, coordinates);
+            }
+        }
+        joined
+    }
+
+    /// Start a pipeline of conversions which are fed through channels, e.g. for ETL code which
+    /// passes records between threads over channels. Every `Job` sent to the returned sender
+    /// is converted with the `defaults` of the client, on at most `concurrency` threads, with
+    /// the same rate limit, retries and quota pauses as `convert_to_3wa_bulk`. Its result
+    /// arrives on the returned receiver as a `BatchItem`, of which the `index` is the position
+    /// of the job among all jobs sent. Results arrive in the order the jobs finish.
+    ///
+    /// Both channels hold at most `buffer` items, so sending blocks while the results are not
+    /// received: send and receive on different threads. The receiver ends once every sender is
+    /// dropped and the remaining jobs are done; the jobs are dropped once the receiver is.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use std::thread;
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let three_words = vec![String::from("filled.count.soap")];
+    /// let w3_client = Arc::new(W3WClient::new("your_api_key"));
+    /// let (jobs, results) = w3_client.pipeline(8, 100);
+    /// thread::spawn(move || {
+    ///     for words in three_words {
+    ///         jobs.send(Job::ConvertToCoordinates(words)).unwrap();
+    ///     }
+    /// });
+    /// for item in results {
+    ///     match item.result {
+    ///         Ok(address) => println!("{:?} is at {}", item.input, address.coordinates),
+    ///         Err(error) => eprintln!("{:?} failed: {}", item.input, error),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pipeline(
+        self: &Arc<Self>,
+        concurrency: usize,
+        buffer: usize,
+    ) -> (SyncSender<Job>, Receiver<BatchItem<Job, Address>>) {
+        let (job_sender, jobs) = mpsc::sync_channel::<Job>(buffer);
+        let (result_sender, results) = mpsc::sync_channel(buffer);
+        let client = Arc::clone(self);
+        thread::spawn(move || {
+            let gate = client.quota_gate();
+            // The jobs with the number of jobs taken so far, which is the index of the next job.
+            let jobs = Mutex::new((jobs, 0));
+            thread::scope(|scope| {
+                for _ in 0..concurrency.max(1) {
+                    let result_sender = result_sender.clone();
+                    let (client, gate, jobs) = (&client, &gate, &jobs);
+                    scope.spawn(move || loop {
+                        let (index, job) = {
+                            let mut jobs =
+                                jobs.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                            match jobs.0.recv() {
+                                Ok(job) => {
+                                    jobs.1 += 1;
+                                    (jobs.1 - 1, job)
+                                }
+                                Err(_) => return,
+                            }
+                        };
+                        let result =
+                            gate.call(|| with_retries(gate.events, || client.run_job(&job)));
+                        let item = BatchItem {
+                            index,
+                            input: job,
+                            result,
+                        };
+                        if result_sender.send(item).is_err() {
+                            return;
+                        }
+                    });
+                }
+            });
+        });
+        (job_sender, results)
+    }
+
+    /// Make the conversion of a `Job` of a `pipeline`.
+    fn run_job(&self, job: &Job) -> Result<Address, W3WError> {
+        match job {
+            Job::ConvertTo3WA(coordinates) => {
+                self.convert_to_3wa_typed(coordinates, &ConvertTo3WAOptions::default())
+            }
+            Job::ConvertToCoordinates(three_words) => self
+                .convert_to_coordinates_typed(three_words, &ConvertToCoordinatesOptions::default()),
+        }
+    }
+
+    /// Convert many coordinates to 3word addresses like `convert_to_3wa_bulk`, on the rayon
+    /// thread pool instead of threads of its own, e.g. within a script which already uses
+    /// rayon. The calls on all threads of the pool wait for the `rate_limit` of the client, and
+    /// a call which fails with an error which `is_transient` is tried up to 3 times. Returns the
+    /// result of every coordinate in the order of `coordinates`. Requires the `rayon` feature.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let w3_client = W3WClient::new("your_api_key");
+    /// # let points = vec![Coordinate { latitude: 51.520847, longitude: -0.195521 }];
+    /// let results = w3_client.convert_to_3wa_par_bulk(&points, &ConvertTo3WAOptions::default());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn convert_to_3wa_par_bulk(
+        &self,
+        coordinates: &[Coordinate],
+        options: &ConvertTo3WAOptions,
+    ) -> Vec<Result<Address, W3WError>> {
+        run_par_bulk(&self.quota_gate(), coordinates, |coordinate| {
+            self.convert_to_3wa_typed(coordinate, options)
+        })
+    }
+
+    /// Convert many 3word addresses to coordinates like `convert_to_coordinates_bulk`, on the
+    /// rayon thread pool, like `convert_to_3wa_par_bulk`. Requires the `rayon` feature.
+    #[cfg(feature = "rayon")]
+    pub fn convert_to_coordinates_par_bulk(
+        &self,
+        three_words: impl IntoIterator<Item = impl AsRef<str>>,
+        options: &ConvertToCoordinatesOptions,
+    ) -> Vec<Result<Coordinate, W3WError>> {
+        let three_words: Vec<String> = three_words
+            .into_iter()
+            .map(|words| words.as_ref().to_string())
+            .collect();
+        run_par_bulk(&self.quota_gate(), &three_words, |words| {
+            self.convert_to_coordinates_typed(words, options)
+                .map(|address| address.coordinates)
+        })
+    }
 }
 
-/// Fetch the JSON body from a Response.
-fn get_json(resp: Result<Response, Response>) -> Result<Value, Response> {
-    let json: Value = resp?
-        .json()
-        .expect("An error occurred while extracting JSON from response");
-    Ok(json)
+/// Return the key of the `AddressCache` for the addresses of a `convert_to_3wa` call with
+/// `options`.
+fn address_cache_key(options: &ConvertTo3WAOptions) -> Result<String, W3WError> {
+    query_string(&[], options)
+}
+
+/// Call `f` for every item on at most `concurrency` threads and return the results in the order
+/// of the items.
+fn run_concurrently<T: Sync, R: Send>(
+    items: &[T],
+    concurrency: usize,
+    f: impl Fn(&T) -> R + Sync,
+) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..concurrency.clamp(1, items.len().max(1)))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        match items.get(index) {
+                            Some(item) => results.push((index, f(item))),
+                            None => return results,
+                        }
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("a worker thread panicked"))
+            .collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
 }
 
-/// Check the status code of a response.
-/// If the status code is between 400 and 599, a error will be printed to io::stderr
-fn check_status_code(response: Response) -> Result<Response, Response> {
-    let status_code = response.status();
-    if status_code.is_client_error() || status_code.is_server_error() {
-        eprintln!(
-            "The response returned an error, status code: {}",
-            status_code
+/// Call `f` for every item of a bulk call like `run_concurrently`, retrying transient errors
+/// with `with_retries` and pausing on quota errors with `gate`, and call `on_progress` with the
+/// number of finished items, the number of items and the result after every item.
+fn run_bulk<T: Sync, R: Send>(
+    gate: &QuotaGate,
+    items: &[T],
+    concurrency: usize,
+    f: impl Fn(&T) -> Result<R, W3WError> + Sync,
+    on_progress: impl Fn(usize, usize, &Result<R, W3WError>) + Sync,
+) -> Vec<Result<R, W3WError>> {
+    let done = AtomicUsize::new(0);
+    run_concurrently(items, concurrency, |item| {
+        let result = gate.call(|| with_retries(gate.events, || f(item)));
+        on_progress(
+            done.fetch_add(1, Ordering::Relaxed) + 1,
+            items.len(),
+            &result,
         );
-        return Err(response);
+        result
+    })
+}
+
+/// Call `f` for every item of a bulk call on the rayon thread pool, retrying transient errors
+/// with `with_retries` and pausing on quota errors with `gate`.
+#[cfg(feature = "rayon")]
+fn run_par_bulk<T: Sync, R: Send>(
+    gate: &QuotaGate,
+    items: &[T],
+    f: impl Fn(&T) -> Result<R, W3WError> + Sync,
+) -> Vec<Result<R, W3WError>> {
+    use rayon::prelude::*;
+
+    items
+        .par_iter()
+        .map(|item| gate.call(|| with_retries(gate.events, || f(item))))
+        .collect()
+}
+
+/// Pauses all calls of a bulk call when one of them runs out of quota, see
+/// `W3WClient::quota_pause`.
+struct QuotaGate<'a> {
+    pause: Option<Duration>,
+    max_pauses: u32,
+    key_pool: Option<&'a KeyPool>,
+    events: Option<&'a dyn EventListener>,
+    state: Mutex<PauseState>,
+}
+
+/// The pauses of a `QuotaGate` so far, and when the current one is over.
+struct PauseState {
+    pauses: u32,
+    resume_at: Option<Instant>,
+}
+
+impl QuotaGate<'_> {
+    /// Call `f`, and while it fails with a quota error, pause all calls for `pause` and call it
+    /// again with the keys of the `key_pool` available again. Without a `pause`, or once the
+    /// calls paused `max_pauses` times, the quota error is returned as is.
+    fn call<R>(&self, f: impl Fn() -> Result<R, W3WError>) -> Result<R, W3WError> {
+        loop {
+            self.wait();
+            let result = f();
+            match (self.pause, &result) {
+                (Some(pause), Err(error)) if error.is_quota_exceeded() && self.pause(pause) => {}
+                _ => return result,
+            }
+        }
+    }
+
+    /// Pause all calls for `pause` from now on, unless they are paused already. Returns
+    /// `false` when the calls paused `max_pauses` times already, so they should give up.
+    fn pause(&self, pause: Duration) -> bool {
+        let mut state = self.lock();
+        if state.resume_at.is_some() {
+            return true;
+        }
+        if state.pauses >= self.max_pauses {
+            return false;
+        }
+        state.pauses += 1;
+        state.resume_at = Some(Instant::now() + pause);
+        drop(state);
+        if let Some(events) = self.events {
+            events.circuit_opened(pause);
+        }
+        true
+    }
+
+    /// Block until the pause, if any, is over. The first call which finds the pause over makes
+    /// the keys of the `key_pool` available again.
+    fn wait(&self) {
+        loop {
+            let mut state = self.lock();
+            match state.resume_at {
+                Some(until) if until > Instant::now() => {
+                    drop(state);
+                    thread::sleep(until.saturating_duration_since(Instant::now()));
+                }
+                Some(_) => {
+                    state.resume_at = None;
+                    if let Some(key_pool) = self.key_pool {
+                        key_pool.reset();
+                    }
+                    return;
+                }
+                None => return,
+            }
+        }
     }
-    Ok(response)
+
+    fn lock(&self) -> MutexGuard<'_, PauseState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Call `f` until it succeeds, fails with an error which is not transient, or was tried
+/// `BULK_ATTEMPTS` times, waiting `BULK_RETRY_DELAY` before the first retry and twice as long
+/// before every next one. Every retry is reported to `events`, if any.
+fn with_retries<R>(
+    events: Option<&dyn EventListener>,
+    f: impl Fn() -> Result<R, W3WError>,
+) -> Result<R, W3WError> {
+    let mut delay = BULK_RETRY_DELAY;
+    for attempt in 1..BULK_ATTEMPTS {
+        match f() {
+            Err(error) if error.is_transient() => {
+                if let Some(events) = events {
+                    events.retry_scheduled(attempt, delay, &error);
+                }
+                thread::sleep(delay)
+            }
+            result => return result,
+        }
+        delay *= 2;
+    }
+    f()
+}
+
+/// Fetch the JSON body from a Response.
+fn get_json(resp: Result<Response, W3WError>) -> Result<Value, W3WError> {
+    decode(resp?)
+}
+
+/// Decode the JSON body of a response into `T`, reporting a body of the wrong shape as a
+/// `W3WError::Decode`.
+fn decode<T: DeserializeOwned>(resp: Response) -> Result<T, W3WError> {
+    let body = resp.text()?;
+    serde_json::from_str(&body).map_err(|error| decode_error("", &error.to_string()))
+}
+
+/// Return the value at the JSON pointer `path`, or a `W3WError::Decode` naming the path.
+fn extract<'v>(json: &'v Value, path: &str) -> Result<&'v Value, W3WError> {
+    json.pointer(path)
+        .ok_or_else(|| decode_error(path, "missing value"))
+}
+
+/// Return the number at the JSON pointer `path`, see `extract`.
+fn extract_f64(json: &Value, path: &str) -> Result<f64, W3WError> {
+    extract(json, path)?
+        .as_f64()
+        .ok_or_else(|| decode_error(path, "expected a number"))
 }
 
-/// Parse the URL based on a given keyword and value.
-fn parse_url(mut url: String, keyword: &str, value: &str) -> String {
-    url.push_str(&format!("&{}={}", keyword, value));
-    url
+/// Return the string at the JSON pointer `path`, see `extract`.
+fn extract_str<'v>(json: &'v Value, path: &str) -> Result<&'v str, W3WError> {
+    extract(json, path)?
+        .as_str()
+        .ok_or_else(|| decode_error(path, "expected a string"))
+}
+
+/// Build a `W3WError::Decode` for the value at `path`.
+fn decode_error(path: &str, message: &str) -> W3WError {
+    W3WError::Decode(DecodeError {
+        path: path.to_string(),
+        message: message.to_string(),
+    })
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{parse_url, AutoSuggestOptions, W3WClient};
+    use std::collections::HashMap;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use reqwest::StatusCode;
+
+    use super::{extract_f64, extract_str};
+    use crate::{
+        Address, AddressCache, ApiKeyLocation, AutoSuggestOptions, AutosuggestCache, BoundingBox,
+        CacheKind, Circle, ConvertTo3WAOptions, ConvertToCoordinatesOptions, Coordinate,
+        Correction, DefaultOptions, DiscrepancyKind, EventListener, GridSectionOptions, InputType,
+        Job, KeyPool, LocationSource, Polygon, RateLimit, ResponseFormat, SourceApi, Square,
+        Suggestion, ThreeWordAddress, W3WClient, W3WError,
+    };
 
     #[test]
-    fn test_parsing_url() {
+    fn test_building_query() {
         let mut w3_client = W3WClient::new("mock-api-key");
         w3_client.host = String::from("https://test.com");
+        w3_client.api_key_location = ApiKeyLocation::Query;
+        let circle = Circle {
+            centerpoint: Coordinate {
+                latitude: 51.0,
+                longitude: 4.0,
+            },
+            radius: 10.0,
+        };
+        let countries = vec!["BE", "NL"];
         let options = AutoSuggestOptions {
+            circle: Some(&circle),
+            countries: Some(&countries),
             language: Some("nl"),
             prefer_land: Some(false),
             ..Default::default()
         };
 
-        let mut url = format!("{}/endpoint?key={}", w3_client.host, w3_client.api_key);
-        if let Some(language) = options.language {
-            url = parse_url(url, "language", language);
-        }
-        if let Some(prefer_land) = options.prefer_land {
-            url = parse_url(url, "prefer-land", &format!("{}", prefer_land));
-        }
+        let request = w3_client
+            .build_request("endpoint", &[("input", "index.home.ra")], &options)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().as_str(),
+            "https://test.com/endpoint?key=mock-api-key&input=index.home.ra\
+             &clip-to-circle=51%2C4%2C10&clip-to-country=BE%2CNL&language=nl&prefer-land=false"
+        );
+    }
+
+    #[test]
+    fn test_building_query_rejects_a_shadowed_parameter() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("https://test.com");
+        let options = ConvertTo3WAOptions {
+            language: Some("nl"),
+            ..Default::default()
+        };
+        assert!(matches!(
+            w3_client.build_request("convert-to-3wa", &[("language", "fr")], &options),
+            Err(W3WError::Validation(_))
+        ));
+        assert!(w3_client
+            .build_request("convert-to-3wa", &[("coordinates", "51,4")], &options)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_endpoint_url_with_trailing_slash() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("https://test.com/v3/");
+        let url = w3_client.endpoint_url("autosuggest").unwrap();
+        assert_eq!(url.as_str(), "https://test.com/v3/autosuggest");
+    }
+
+    #[test]
+    fn test_endpoint_url_with_invalid_host() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        for host in [
+            "api.what3words.com/v3",
+            "https://test.com/v3?x=1",
+            "mailto:a@b.c",
+        ] {
+            w3_client.host = String::from(host);
+            assert!(matches!(
+                w3_client.endpoint_url("autosuggest"),
+                Err(W3WError::Validation(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_query_values_are_percent_encoded() {
+        let w3_client = W3WClient::new("mock-api-key");
+        let countries = vec!["JP"];
+        let options = AutoSuggestOptions {
+            countries: Some(&countries),
+            language: Some("ja"),
+            locale: Some("zh_tr"),
+            ..Default::default()
+        };
+        let request = w3_client
+            .build_request(
+                "autosuggest",
+                &[("input", "こくさい・ていか・かざす&x=1")],
+                &options,
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().query(),
+            Some(
+                "input=%E3%81%93%E3%81%8F%E3%81%95%E3%81%84%E3%83%BB\
+                 %E3%81%A6%E3%81%84%E3%81%8B%E3%83%BB%E3%81%8B%E3%81%96%E3%81%99%26x%3D1\
+                 &clip-to-country=JP&language=ja&locale=zh_tr"
+            )
+        );
+
+        let request = w3_client
+            .build_request(
+                "convert-to-coordinates",
+                &[("words", "filled count soap")],
+                &(),
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(request.url().query(), Some("words=filled+count+soap"));
+    }
+
+    #[test]
+    fn test_response_format_query() {
+        let w3_client = W3WClient::new("mock-api-key");
+        for (format, expected) in [
+            (ResponseFormat::Json, "format=json"),
+            (ResponseFormat::GeoJson, "format=geojson"),
+        ] {
+            let options = ConvertTo3WAOptions {
+                format: Some(format),
+                ..Default::default()
+            };
+            let request = w3_client
+                .build_request("convert-to-3wa", &[], &options)
+                .unwrap()
+                .build()
+                .unwrap();
+            assert_eq!(request.url().query(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_word_address_arguments() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let options = ConvertToCoordinatesOptions::default();
+        let address: ThreeWordAddress = "filled.count.soap".parse().unwrap();
+        let results = [
+            w3_client.convert_to_coordinates("filled.count.soap", &options),
+            w3_client.convert_to_coordinates(String::from("filled.count.soap"), &options),
+            w3_client.convert_to_coordinates(&address, &options),
+            w3_client.convert_to_coordinates(address, &options),
+        ];
+        for result in results {
+            assert!(matches!(result, Err(W3WError::Validation(_))));
+        }
+    }
+
+    #[test]
+    fn test_verify_addresses_reports_failures() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let coordinate = Coordinate {
+            latitude: 51.520847,
+            longitude: -0.195521,
+        };
+        let records = vec![
+            ("a", "filled.count.soap", coordinate),
+            ("b", "index.home.raft", coordinate),
+        ];
+        let discrepancies = w3_client.verify_addresses(records, 5.0);
+        assert_eq!(discrepancies.len(), 2);
+        assert_eq!(discrepancies[1].id, "b");
+        assert_eq!(discrepancies[1].words, "index.home.raft");
+        assert!(matches!(
+            discrepancies[1].kind,
+            DiscrepancyKind::Failed(W3WError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_api_key_in_header() {
+        let w3_client = W3WClient::new("mock-api-key");
+        let request = w3_client
+            .build_request("available-languages", &[], &())
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(request.url().query(), None);
+        assert_eq!(request.headers()["X-Api-Key"], "mock-api-key");
+    }
+
+    #[test]
+    fn test_call_headers_are_added_to_default_headers() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client
+            .default_headers
+            .insert("X-Org-Id", HeaderValue::from_static("org-1"));
+        w3_client
+            .default_headers
+            .insert("X-Team", HeaderValue::from_static("maps"));
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Team", HeaderValue::from_static("routing"));
+        let options = ConvertTo3WAOptions {
+            headers: Some(&headers),
+            ..Default::default()
+        };
+        let request = w3_client
+            .build_request(
+                "convert-to-3wa",
+                &[],
+                &options.or_defaults(&w3_client.defaults),
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(request.headers()["X-Org-Id"], "org-1");
+        assert_eq!(request.headers()["X-Team"], "routing");
+        assert_eq!(request.headers()["X-Api-Key"], "mock-api-key");
+        assert_eq!(request.url().query(), None);
+    }
+
+    #[test]
+    fn test_options_fall_back_to_client_defaults() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.defaults = DefaultOptions {
+            language: Some(String::from("nl")),
+            format: Some(ResponseFormat::GeoJson),
+            countries: Some(vec![String::from("BE")]),
+            ..Default::default()
+        };
+        let options = ConvertTo3WAOptions {
+            language: Some("fr"),
+            ..Default::default()
+        };
+        let merged = options.or_defaults(&w3_client.defaults);
+        assert_eq!(merged.language, Some("fr"));
+        assert_eq!(merged.format, Some(ResponseFormat::GeoJson));
+
+        let default_countries = w3_client.defaults.country_list();
+        let options = AutoSuggestOptions::default();
+        let merged = options.or_defaults(&w3_client.defaults, default_countries.as_ref());
+        let request = w3_client
+            .build_request("autosuggest", &[("input", "filled.count.so")], &merged)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().query(),
+            Some("input=filled.count.so&clip-to-country=BE&language=nl")
+        );
+    }
+
+    #[test]
+    fn test_api_key_is_redacted() {
+        let mut w3_client = W3WClient::new("secret-api-key");
+        assert!(!format!("{:?}", w3_client).contains("secret-api-key"));
+
+        w3_client.host = String::from("http://127.0.0.1:1");
+        w3_client.api_key_location = ApiKeyLocation::Query;
+        let error = w3_client.available_languages().unwrap_err();
+        assert!(matches!(error, W3WError::Request(_)));
+        assert!(!format!("{:?}", error).contains("secret-api-key"));
+        assert!(!error.to_string().contains("secret-api-key"));
+    }
+
+    #[test]
+    fn test_client_from_variables() {
+        let vars = HashMap::from([
+            ("W3W_API_KEY", "mock-api-key"),
+            ("W3W_HOST", "https://test.com/v3"),
+            ("W3W_LANGUAGE", "nl"),
+            ("W3W_TIMEOUT_SECS", "5"),
+        ]);
+        let w3_client = W3WClient::from_vars(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+        assert_eq!(w3_client.api_key, "mock-api-key");
+        assert_eq!(w3_client.host, "https://test.com/v3");
+        assert_eq!(w3_client.defaults.language.as_deref(), Some("nl"));
+
+        assert!(matches!(
+            W3WClient::from_vars(|_| None),
+            Err(W3WError::Validation(_))
+        ));
+        let vars = HashMap::from([("W3W_API_KEY", "mock-api-key"), ("W3W_TIMEOUT_SECS", "5s")]);
+        assert!(matches!(
+            W3WClient::from_vars(|name| vars.get(name).map(|v| v.to_string())),
+            Err(W3WError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_api_key_returns_other_errors() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        assert!(matches!(
+            w3_client.validate_api_key(),
+            Err(W3WError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_number_of_results() {
+        let w3_client = W3WClient::new("mock-api-key");
+        let options = AutoSuggestOptions {
+            n_results: Some(10),
+            n_focus_results: Some(5),
+            ..Default::default()
+        };
+        let request = w3_client
+            .build_request("autosuggest", &[], &options)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().query(),
+            Some("n-results=10&n-focus-results=5")
+        );
+
+        for (n_results, n_focus_results) in [(Some(0), None), (Some(101), None), (Some(3), Some(5))]
+        {
+            let options = AutoSuggestOptions {
+                n_results,
+                n_focus_results,
+                ..Default::default()
+            };
+            assert!(matches!(
+                w3_client.autosuggest("filled.count.so", &options),
+                Err(W3WError::Validation(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_requests_wait_for_the_rate_limit() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("http://127.0.0.1:1");
+        w3_client.rate_limit = Some(RateLimit::new(1, Duration::from_millis(100)));
+        let start = Instant::now();
+        for _ in 0..2 {
+            let result = w3_client.available_languages();
+            assert!(matches!(result, Err(W3WError::Request(_))));
+        }
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[test]
+    fn test_invalid_coordinates_are_not_sent() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("http://127.0.0.1:1");
+        let swapped = Coordinate {
+            latitude: 151.2,
+            longitude: -33.8,
+        };
+        let result = w3_client.convert_to_3wa(swapped, &ConvertTo3WAOptions::default());
+        assert!(matches!(result, Err(W3WError::Validation(_))));
+
+        let focus = Coordinate {
+            latitude: f64::NAN,
+            longitude: 4.0,
+        };
+        let options = AutoSuggestOptions {
+            focus_coordinates: Some(&focus),
+            ..Default::default()
+        };
+        let result = w3_client.autosuggest("filled.count.so", &options);
+        assert!(matches!(result, Err(W3WError::Validation(_))));
+    }
+
+    #[test]
+    fn test_outgoing_coordinates_are_normalized_on_request() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("http://127.0.0.1:1");
+        let wrapped = Coordinate {
+            latitude: 51.5,
+            longitude: 364.0,
+        };
+        let result = w3_client.convert_to_3wa(wrapped, &ConvertTo3WAOptions::default());
+        assert!(matches!(result, Err(W3WError::Validation(_))));
+
+        w3_client.normalize_coordinates = true;
+        match w3_client.convert_to_3wa((51.5, 364.0), &ConvertTo3WAOptions::default()) {
+            Err(W3WError::Request(error)) => {
+                let query = error.url().unwrap().query().unwrap().to_string();
+                assert!(query.contains("coordinates=51.5%2C4"), "{}", query);
+            }
+            other => panic!("expected a request error, got {:?}", other),
+        }
+        let options = AutoSuggestOptions {
+            focus_coordinates: Some(&wrapped),
+            ..Default::default()
+        };
+        match w3_client.autosuggest("filled.count.so", &options) {
+            Err(W3WError::Request(error)) => {
+                let query = error.url().unwrap().query().unwrap().to_string();
+                assert!(query.contains("focus=51.5%2C4"), "{}", query);
+            }
+            other => panic!("expected a request error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_outgoing_coordinates_are_rounded() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("http://127.0.0.1:1");
+        let precise = Coordinate {
+            latitude: 51.520847123,
+            longitude: -0.195521456,
+        };
+        let query_of = |result: Result<reqwest::blocking::Response, W3WError>| match result {
+            Err(W3WError::Request(error)) => error.url().unwrap().query().unwrap().to_string(),
+            other => panic!("expected a request error, got {:?}", other),
+        };
+        let query = query_of(w3_client.convert_to_3wa(precise, &ConvertTo3WAOptions::default()));
+        assert!(
+            query.contains("coordinates=51.520847%2C-0.195521"),
+            "{}",
+            query
+        );
+
+        let circle = Circle::from_meters(precise, 500.0);
+        let options = AutoSuggestOptions {
+            focus_coordinates: Some(&precise),
+            circle: Some(&circle),
+            ..Default::default()
+        };
+        let query = query_of(w3_client.autosuggest("filled.count.so", &options));
+        assert!(query.contains("focus=51.520847%2C-0.195521"), "{}", query);
+        assert!(
+            query.contains("clip-to-circle=51.520847%2C-0.195521%2C0.5"),
+            "{}",
+            query
+        );
+
+        w3_client.coordinate_precision = None;
+        let query = query_of(w3_client.convert_to_3wa(precise, &ConvertTo3WAOptions::default()));
+        assert!(
+            query.contains("coordinates=51.520847123%2C-0.195521456"),
+            "{}",
+            query
+        );
+    }
+
+    #[test]
+    fn test_strict_coordinates_reject_null_island() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("http://127.0.0.1:1");
+        let null_island = Coordinate {
+            latitude: 0.0,
+            longitude: 0.0,
+        };
+        let result = w3_client.convert_to_3wa(null_island, &ConvertTo3WAOptions::default());
+        assert!(matches!(result, Err(W3WError::Request(_))));
+
+        w3_client.strict_coordinates = true;
+        let result = w3_client.convert_to_3wa(null_island, &ConvertTo3WAOptions::default());
+        assert!(matches!(result, Err(W3WError::Validation(_))));
+        let options = AutoSuggestOptions {
+            focus_coordinates: Some(&null_island),
+            ..Default::default()
+        };
+        let result = w3_client.autosuggest("filled.count.so", &options);
+        assert!(matches!(result, Err(W3WError::Validation(_))));
+    }
+
+    #[test]
+    fn test_autosuggest_rejects_invalid_polygon() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("http://127.0.0.1:1");
+        let line = Polygon {
+            coordinates: vec![Coordinate::from((51.0, 4.0)), Coordinate::from((52.0, 4.5))],
+        };
+        let options = AutoSuggestOptions {
+            polygon: Some(&line),
+            ..Default::default()
+        };
+        let result = w3_client.autosuggest("filled.count.so", &options);
+        assert!(matches!(result, Err(W3WError::Validation(_))));
+
+        w3_client.defaults.polygon = Some(line);
+        let result = w3_client.autosuggest("filled.count.so", &AutoSuggestOptions::default());
+        assert!(matches!(result, Err(W3WError::Validation(_))));
+    }
+
+    #[test]
+    fn test_autosuggest_sends_normalized_polygon() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("http://127.0.0.1:1");
+        let clockwise = Polygon {
+            coordinates: [
+                (51.0, 4.0),
+                (52.0, 4.0),
+                (52.0, 5.0),
+                (52.0, 5.0),
+                (51.0, 4.0),
+            ]
+            .into_iter()
+            .map(Coordinate::from)
+            .collect(),
+        };
+        let options = AutoSuggestOptions {
+            polygon: Some(&clockwise),
+            ..Default::default()
+        };
+        match w3_client.autosuggest("filled.count.so", &options) {
+            Err(W3WError::Request(error)) => {
+                let query = error.url().unwrap().query().unwrap().to_string();
+                assert!(
+                    query.contains("clip-to-polygon=51%2C4%2C52%2C5%2C52%2C4%2C51%2C4"),
+                    "{}",
+                    query
+                );
+            }
+            other => panic!("expected a request error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_autosuggest_simplifies_large_polygon_on_request() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("http://127.0.0.1:1");
+        let traced = Polygon {
+            coordinates: (0..40)
+                .map(|i| {
+                    let angle = f64::from(i * 9).to_radians();
+                    Coordinate::from((51.5 + angle.sin() / 10.0, -0.1 + angle.cos() / 10.0))
+                })
+                .collect(),
+        };
+        let options = AutoSuggestOptions {
+            polygon: Some(&traced),
+            ..Default::default()
+        };
+        let result = w3_client.autosuggest("filled.count.so", &options);
+        assert!(matches!(result, Err(W3WError::Validation(_))));
+
+        let options = AutoSuggestOptions {
+            simplify_polygon: true,
+            ..options
+        };
+        match w3_client.autosuggest("filled.count.so", &options) {
+            Err(W3WError::Request(error)) => {
+                let url = error.url().unwrap();
+                let (_, polygon) = url
+                    .query_pairs()
+                    .find(|(name, _)| name == "clip-to-polygon")
+                    .unwrap();
+                assert_eq!(polygon.split(',').count(), 2 * 25);
+            }
+            other => panic!("expected a request error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_voice_input_type() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("http://127.0.0.1:1");
+        let options = AutoSuggestOptions {
+            input_type: Some(InputType::GenericVoice),
+            language: Some("en"),
+            ..Default::default()
+        };
+        let request = w3_client
+            .build_request("autosuggest", &[], &options)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().query(),
+            Some("language=en&input-type=generic-voice")
+        );
+
+        let options = AutoSuggestOptions {
+            input_type: Some(InputType::NmdpAsr),
+            ..Default::default()
+        };
+        assert!(matches!(
+            w3_client.autosuggest("filled count soap", &options),
+            Err(W3WError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_autosuggest_selection_query() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        assert!(matches!(
+            w3_client.autosuggest_selection(
+                "filled.count.so",
+                "filled.count.soap",
+                1,
+                SourceApi::Text
+            ),
+            Err(W3WError::Validation(_))
+        ));
+
+        let w3_client = W3WClient::new("mock-api-key");
+        let request = w3_client
+            .build_request(
+                "autosuggest-selection",
+                &[
+                    ("raw-input", "filled count so"),
+                    ("source-api", SourceApi::Voice.as_str()),
+                ],
+                &(),
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().query(),
+            Some("raw-input=filled+count+so&source-api=voice")
+        );
+    }
+
+    #[test]
+    fn test_clip_to_country_codes() {
+        let w3_client = W3WClient::new("mock-api-key");
+        let countries = vec!["be", "Nl"];
+        let options = AutoSuggestOptions {
+            countries: Some(&countries),
+            ..Default::default()
+        };
+        let request = w3_client
+            .build_request("autosuggest", &[], &options)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(request.url().query(), Some("clip-to-country=BE%2CNL"));
+
+        let countries = vec!["BE", "BEL"];
+        let options = AutoSuggestOptions {
+            countries: Some(&countries),
+            ..Default::default()
+        };
+        assert!(matches!(
+            w3_client.autosuggest("filled.count.so", &options),
+            Err(W3WError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_extraction_errors_name_the_path() {
+        let json = serde_json::json!({"words": "filled.count.soap", "coordinates": {"lat": "51"}});
+        assert_eq!(extract_str(&json, "/words").unwrap(), "filled.count.soap");
+        for (path, message) in [
+            ("/coordinates/lat", "expected a number"),
+            ("/coordinates/lng", "missing value"),
+        ] {
+            match extract_f64(&json, path) {
+                Err(W3WError::Decode(error)) => {
+                    assert_eq!(error.path, path);
+                    assert_eq!(error.message, message);
+                }
+                other => panic!("expected a decode error, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_error_response_body_is_captured() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let _ = stream.read(&mut request).unwrap();
+            let body = r#"{"error":{"code":"BadInput","message":"Invalid input"}}"#;
+            write!(
+                stream,
+                "HTTP/1.1 400 Bad Request\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        });
+
+        let mut w3_client = W3WClient::new("secret-api-key");
+        w3_client.host = format!("http://127.0.0.1:{}", port);
+        w3_client.api_key_location = ApiKeyLocation::Query;
+        w3_client.error_body_limit = 20;
+        match w3_client.available_languages() {
+            Err(W3WError::Response(response)) => {
+                assert_eq!(response.status, StatusCode::BAD_REQUEST);
+                assert_eq!(response.body, r#"{"error":{"code":"Ba"#);
+                assert!(!response.url.contains("secret-api-key"));
+            }
+            other => panic!("expected an error response, got {:?}", other),
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_resolve_suggestions() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let suggestion = |words: &str, latitude: Option<f64>| Suggestion {
+            country: "GB".parse().unwrap(),
+            nearest_place: String::from("Bayswater, London"),
+            words: words.to_string(),
+            rank: 1,
+            language: String::from("en"),
+            distance_to_focus_km: None,
+            square: None,
+            coordinates: latitude.map(|latitude| Coordinate {
+                latitude,
+                longitude: -0.19,
+            }),
+            map: None,
+        };
+        let suggestions = vec![
+            suggestion("filled.count.soap", Some(51.1)),
+            suggestion("filled.count.soaps", Some(51.2)),
+            suggestion("filled.count.sand", Some(51.3)),
+            suggestion("filled.count.song", None),
+        ];
+
+        let resolved = w3_client.resolve_suggestions(&suggestions, 3, 2).unwrap();
+        let latitudes: Vec<f64> = resolved.iter().map(|(_, c)| c.latitude).collect();
+        assert_eq!(latitudes, [51.1, 51.2, 51.3]);
+        assert_eq!(resolved[2].0.words, "filled.count.sand");
+
+        assert!(matches!(
+            w3_client.resolve_suggestions(&suggestions, 10, 4),
+            Err(W3WError::Validation(_))
+        ));
+        assert!(w3_client
+            .resolve_suggestions(&suggestions, 0, 0)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_resolve_offline_inputs() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+
+        let location = w3_client.resolve(" 51.520847, -0.195521").unwrap().unwrap();
+        assert_eq!(location.source, LocationSource::Coordinates);
+        assert_eq!(location.coordinate.longitude, -0.195521);
+
+        for input in ["51°31'15\"N 0°11'44\"W", "+51.520847-000.195521/"] {
+            let location = w3_client.resolve(input).unwrap().unwrap();
+            assert_eq!(location.source, LocationSource::Coordinates);
+            assert!((location.coordinate.latitude - 51.52).abs() < 0.001);
+        }
+
+        let location = w3_client.resolve("8FVC9G8F+6X").unwrap().unwrap();
+        assert_eq!(location.source, LocationSource::PlusCode);
+        assert_eq!(location.words, None);
+
+        for input in ["151.2,-33.8", "///filled.count.soap", "somewhere in London"] {
+            assert!(matches!(
+                w3_client.resolve(input),
+                Err(W3WError::Validation(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_autosuggest_cache_answers_repeated_input() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let cache = AutosuggestCache::new(10);
+        cache.insert(
+            String::from("input=filled.count.so&language=en"),
+            Vec::new(),
+        );
+        w3_client.autosuggest_cache = Some(cache);
+        let options = AutoSuggestOptions {
+            language: Some("en"),
+            ..Default::default()
+        };
+
+        let suggestions = w3_client
+            .autosuggest_typed("///filled.count.so", &options)
+            .unwrap();
+        assert!(suggestions.is_empty());
+        assert!(matches!(
+            w3_client.autosuggest_typed("filled.count.so", &AutoSuggestOptions::default()),
+            Err(W3WError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_suggestion_ranker_reorders_suggestions() {
+        let suggestion = |words: &str, country: &str, rank: u32| Suggestion {
+            country: country.parse().unwrap(),
+            nearest_place: String::from("Somewhere"),
+            words: words.to_string(),
+            rank,
+            language: String::from("en"),
+            distance_to_focus_km: None,
+            square: None,
+            coordinates: None,
+            map: None,
+        };
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let cache = AutosuggestCache::new(10);
+        cache.insert(
+            String::from("input=filled.count.so"),
+            vec![
+                suggestion("filled.count.soap", "GB", 1),
+                suggestion("filled.count.soaps", "BE", 2),
+                suggestion("filled.count.sock", "BE", 3),
+            ],
+        );
+        w3_client.autosuggest_cache = Some(cache);
+        w3_client.suggestion_ranker = Some(Box::new(|suggestion: &Suggestion| {
+            if suggestion.country.as_str() == "BE" {
+                1.0
+            } else {
+                0.0
+            }
+        }));
+
+        let suggestions = w3_client
+            .autosuggest_typed("filled.count.so", &AutoSuggestOptions::default())
+            .unwrap();
+        let ranked: Vec<(&str, u32)> = suggestions
+            .iter()
+            .map(|suggestion| (suggestion.words.as_str(), suggestion.rank))
+            .collect();
+        assert_eq!(
+            ranked,
+            [
+                ("filled.count.soaps", 2),
+                ("filled.count.sock", 3),
+                ("filled.count.soap", 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grid_section_rejects_large_bounding_box() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("http://127.0.0.1:1");
+        let bounding_box = BoundingBox {
+            south_west: Coordinate {
+                latitude: 51.5,
+                longitude: -0.2,
+            },
+            north_east: Coordinate {
+                latitude: 51.53,
+                longitude: -0.15,
+            },
+        };
+        match w3_client.grid_section(&bounding_box, &GridSectionOptions::default()) {
+            Err(W3WError::Validation(message)) => {
+                assert!(
+                    message.contains("4.81 km from corner to corner"),
+                    "{}",
+                    message
+                );
+                assert!(
+                    message.contains("3.46 km wide and 3.34 km high"),
+                    "{}",
+                    message
+                );
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+
+        let bounding_box = BoundingBox {
+            south_west: Coordinate {
+                latitude: 51.520833,
+                longitude: -0.195543,
+            },
+            north_east: Coordinate {
+                latitude: 51.52086,
+                longitude: -0.195499,
+            },
+        };
+        assert!(matches!(
+            w3_client.grid_section(&bounding_box, &GridSectionOptions::default()),
+            Err(W3WError::Request(_))
+        ));
+    }
+
+    #[test]
+    fn test_grid_section_lines_stop_at_the_first_error() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("http://127.0.0.1:1");
+        let city = BoundingBox {
+            south_west: Coordinate {
+                latitude: 51.45,
+                longitude: -0.25,
+            },
+            north_east: Coordinate {
+                latitude: 51.55,
+                longitude: -0.05,
+            },
+        };
+        let lines: Vec<_> = w3_client
+            .grid_section_lines(&city, &GridSectionOptions::default())
+            .collect();
+        assert_eq!(lines.len(), 1);
+        assert!(matches!(lines[0], Err(W3WError::Request(_))));
+    }
+
+    #[test]
+    fn test_grid_section_tiled_rejects_too_many_tiles() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("http://127.0.0.1:1");
+        let country = BoundingBox {
+            south_west: Coordinate {
+                latitude: 49.5,
+                longitude: 2.5,
+            },
+            north_east: Coordinate {
+                latitude: 51.5,
+                longitude: 6.4,
+            },
+        };
+        assert!(matches!(
+            w3_client.grid_section_tiled(&country, &GridSectionOptions::default(), 4),
+            Err(W3WError::Validation(message)) if message.contains("grid section tiles")
+        ));
+        let lines: Vec<_> = w3_client
+            .grid_section_lines(&country, &GridSectionOptions::default())
+            .collect();
+        assert_eq!(lines.len(), 1);
+        assert!(matches!(lines[0], Err(W3WError::Validation(_))));
+    }
+
+    /// A square of the size of the squares in London, with its southwestern corner at the
+    /// given coordinate.
+    fn square(latitude: f64, longitude: f64) -> Square {
+        Square {
+            south_west: Coordinate {
+                latitude,
+                longitude,
+            },
+            north_east: Coordinate {
+                latitude: latitude + 0.000027,
+                longitude: longitude + 0.000044,
+            },
+        }
+    }
+
+    /// An English address of the square.
+    fn address(words: &str, square: Square) -> Address {
+        Address {
+            country: "GB".parse().unwrap(),
+            square,
+            nearest_place: String::from("Bayswater, London"),
+            coordinates: square.center(),
+            words: words.to_string(),
+            language: String::from("en"),
+            locale: None,
+            map: format!("https://w3w.co/{}", words),
+        }
+    }
+
+    #[test]
+    fn test_neighbors_are_answered_from_the_address_cache() {
+        let center = square(51.520833, -0.195543);
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let cache = AddressCache::new(10);
+        for (words, neighbor) in [
+            ("north.of.soap", square(51.52086, -0.195543)),
+            ("east.of.soap", square(51.520833, -0.195499)),
+            ("south.of.soap", square(51.520806, -0.195543)),
+        ] {
+            cache.insert(String::from("format=json"), address(words, neighbor));
+        }
+        w3_client.address_cache = Some(cache);
+
+        assert!(matches!(
+            w3_client.neighbors(&center, &ConvertTo3WAOptions::default()),
+            Err(W3WError::Validation(_))
+        ));
+        w3_client.address_cache.as_ref().unwrap().insert(
+            String::from("format=json"),
+            address("west.of.soap", square(51.520833, -0.195587)),
+        );
+        let neighbors = w3_client
+            .neighbors(&center, &ConvertTo3WAOptions::default())
+            .unwrap();
+        assert_eq!(neighbors.north.words, "north.of.soap");
+        assert_eq!(neighbors.east.words, "east.of.soap");
+        assert_eq!(neighbors.south.words, "south.of.soap");
+        assert_eq!(neighbors.west.words, "west.of.soap");
+    }
+
+    #[test]
+    fn test_snap_to_square_uses_any_cached_square() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let soap = square(51.520833, -0.195543);
+        let cache = AddressCache::new(10);
+        cache.insert(
+            String::from("language=nl&format=json"),
+            address("filled.count.soap", soap),
+        );
+        w3_client.address_cache = Some(cache);
+
+        let position = soap.center().destination(30.0, 0.5);
+        assert_eq!(w3_client.snap_to_square(position).unwrap(), soap);
+        assert!(matches!(
+            w3_client.snap_to_square((51.6, -0.195543)),
+            Err(W3WError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_convert_to_3wa_bulk_keeps_the_input_order() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let cache = AddressCache::new(10);
+        let soap = square(51.520833, -0.195543);
+        let sand = square(51.520806, -0.195543);
+        cache.insert(
+            String::from("format=json"),
+            address("filled.count.soap", soap),
+        );
+        cache.insert(
+            String::from("format=json"),
+            address("filled.count.sand", sand),
+        );
+        w3_client.address_cache = Some(cache);
+
+        let coordinates = [
+            sand.center(),
+            Coordinate::from((51.6, -0.195543)),
+            soap.center(),
+            Coordinate::from((f64::NAN, 4.0)),
+            sand.center(),
+        ];
+        let results =
+            w3_client.convert_to_3wa_bulk(&coordinates, &ConvertTo3WAOptions::default(), 3);
+        let words: Vec<Option<&str>> = results
+            .iter()
+            .map(|result| result.as_ref().ok().map(|address| address.words.as_str()))
+            .collect();
+        assert_eq!(
+            words,
+            [
+                Some("filled.count.sand"),
+                None,
+                Some("filled.count.soap"),
+                None,
+                Some("filled.count.sand")
+            ]
+        );
+        assert!(matches!(results[1], Err(W3WError::Validation(_))));
+    }
+
+    #[test]
+    fn test_convert_to_3wa_bulk_reports_progress() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let soap = square(51.520833, -0.195543);
+        let cache = AddressCache::new(10);
+        cache.insert(
+            String::from("format=json"),
+            address("filled.count.soap", soap),
+        );
+        w3_client.address_cache = Some(cache);
+
+        let coordinates = [
+            soap.center(),
+            Coordinate::from((51.6, -0.195543)),
+            soap.center(),
+        ];
+        let progress = Mutex::new(Vec::new());
+        let results = w3_client.convert_to_3wa_bulk_with_progress(
+            &coordinates,
+            &ConvertTo3WAOptions::default(),
+            2,
+            |done, total, result| progress.lock().unwrap().push((done, total, result.is_ok())),
+        );
+        assert_eq!(results.len(), 3);
+        let mut progress = progress.into_inner().unwrap();
+        progress.sort();
+        assert_eq!(
+            progress.iter().map(|(done, ..)| *done).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        assert!(progress.iter().all(|(_, total, _)| *total == 3));
+        assert_eq!(progress.iter().filter(|(.., ok)| *ok).count(), 2);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_convert_to_3wa_par_bulk_keeps_the_input_order() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let soap = square(51.520833, -0.195543);
+        let cache = AddressCache::new(10);
+        cache.insert(
+            String::from("format=json"),
+            address("filled.count.soap", soap),
+        );
+        w3_client.address_cache = Some(cache);
+
+        let coordinates = [
+            Coordinate::from((51.6, -0.195543)),
+            soap.center(),
+            Coordinate::from((f64::NAN, 4.0)),
+        ];
+        let results =
+            w3_client.convert_to_3wa_par_bulk(&coordinates, &ConvertTo3WAOptions::default());
+        assert!(matches!(results[0], Err(W3WError::Validation(_))));
+        assert_eq!(results[1].as_ref().unwrap().words, "filled.count.soap");
+        assert!(matches!(results[2], Err(W3WError::Validation(_))));
+    }
+
+    #[test]
+    fn test_bulk_calls_pause_when_the_quota_runs_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let body = include_str!("../tests/fixtures/convert_to_coordinates.json");
+            for status in ["402 Payment Required", "402 Payment Required", "200 OK"] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 4096];
+                let _ = stream.read(&mut request).unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = format!("http://127.0.0.1:{}", port);
+        let results = w3_client.convert_to_coordinates_bulk(
+            ["filled.count.soap"],
+            &ConvertToCoordinatesOptions::default(),
+            1,
+        );
+        assert!(results[0].as_ref().unwrap_err().is_quota_exceeded());
+
+        w3_client.quota_pause = Some(Duration::from_millis(50));
+        let start = Instant::now();
+        let results = w3_client.convert_to_coordinates_bulk(
+            ["filled.count.soap"],
+            &ConvertToCoordinatesOptions::default(),
+            1,
+        );
+        server.join().unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert_eq!(
+            results[0].as_ref().unwrap(),
+            &Coordinate::from((51.520847, -0.195521))
+        );
+    }
+
+    #[test]
+    fn test_bulk_calls_give_up_when_the_key_pool_stays_out_of_quota() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request = [0; 4096];
+                let _ = stream.read(&mut request);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 402 Payment Required\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                );
+            }
+        });
+
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = format!("http://127.0.0.1:{}", port);
+        w3_client.key_pool = Some(KeyPool::new(["key-a", "key-b"]));
+        let three_words = ["filled.count.soap"; 8];
+        let progress = AtomicUsize::new(0);
+        let results = w3_client.convert_to_coordinates_bulk_with_progress(
+            three_words,
+            &ConvertToCoordinatesOptions::default(),
+            4,
+            |_, _, _| {
+                progress.fetch_add(1, Ordering::SeqCst);
+            },
+        );
+        assert_eq!(progress.load(Ordering::SeqCst), 8);
+        assert!(results
+            .iter()
+            .all(|result| result.as_ref().unwrap_err().is_quota_exceeded()));
+        assert!(results.iter().any(|result| matches!(
+            result,
+            Err(W3WError::Response(response))
+                if response.body == "all keys of the key pool are exhausted"
+        )));
+
+        w3_client.quota_pause = Some(Duration::from_millis(30));
+        w3_client.quota_max_pauses = 2;
+        let start = Instant::now();
+        let results = w3_client.convert_to_coordinates_bulk(
+            three_words,
+            &ConvertToCoordinatesOptions::default(),
+            4,
+        );
+        assert!(start.elapsed() >= Duration::from_millis(60));
+        assert_eq!(results.len(), 8);
+        assert!(results
+            .iter()
+            .all(|result| result.as_ref().unwrap_err().is_quota_exceeded()));
+    }
+
+    #[test]
+    fn test_convert_to_coordinates_bulk_retries_transient_errors() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let body = include_str!("../tests/fixtures/convert_to_coordinates.json");
+            for status in ["503 Service Unavailable", "200 OK"] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 4096];
+                let _ = stream.read(&mut request).unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = format!("http://127.0.0.1:{}", port);
+        let results = w3_client.convert_to_coordinates_bulk(
+            ["filled.count.soap"],
+            &ConvertToCoordinatesOptions::default(),
+            1,
+        );
+        server.join().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0].as_ref().unwrap(),
+            &Coordinate::from((51.520847, -0.195521))
+        );
+    }
+
+    #[test]
+    fn test_join_coordinates_sets_the_coordinates_of_the_converted_records() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let soap = square(51.520833, -0.195543);
+        let cache = AddressCache::new(10);
+        cache.insert(
+            String::from("format=json"),
+            address("filled.count.soap", soap),
+        );
+        w3_client.address_cache = Some(cache);
+
+        let records = vec![
+            (String::from("filled.count.soap"), None),
+            (String::from("index.home.raft"), None),
+            (String::from("filled.count.soap"), None),
+        ];
+        let joined = w3_client.join_coordinates(
+            records,
+            |(words, _)| words.as_str(),
+            |(_, coordinates), found| *coordinates = Some(found),
+            &ConvertToCoordinatesOptions::default(),
+            2,
+        );
+        let coordinates: Vec<(usize, &str, Option<Coordinate>)> = joined
+            .items
+            .iter()
+            .map(|item| (item.index, item.input.0.as_str(), item.input.1))
+            .collect();
+        assert_eq!(
+            coordinates,
+            [
+                (0, "filled.count.soap", Some(soap.center())),
+                (1, "index.home.raft", None),
+                (2, "filled.count.soap", Some(soap.center())),
+            ]
+        );
+        assert!(matches!(
+            joined.failures().next(),
+            Some((item, W3WError::Validation(_))) if item.index == 1
+        ));
+    }
+
+    #[test]
+    fn test_pipeline_converts_the_jobs_sent_to_it() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let soap = square(51.520833, -0.195543);
+        let cache = AddressCache::new(10);
+        cache.insert(
+            String::from("format=json"),
+            address("filled.count.soap", soap),
+        );
+        w3_client.address_cache = Some(cache);
+        let w3_client = Arc::new(w3_client);
+
+        let (jobs, results) = w3_client.pipeline(2, 1);
+        let producer = thread::spawn(move || {
+            jobs.send(Job::ConvertToCoordinates(String::from("filled.count.soap")))
+                .unwrap();
+            jobs.send(Job::ConvertTo3WA(Coordinate::from((f64::NAN, 4.0))))
+                .unwrap();
+            jobs.send(Job::ConvertTo3WA(soap.center())).unwrap();
+        });
+        let mut items: Vec<_> = results.into_iter().collect();
+        producer.join().unwrap();
+        items.sort_by_key(|item| item.index);
+
+        assert_eq!(items.len(), 3);
+        assert_eq!(
+            items[0].input,
+            Job::ConvertToCoordinates(String::from("filled.count.soap"))
+        );
+        assert_eq!(items[0].result.as_ref().unwrap().coordinates, soap.center());
+        assert!(matches!(items[1].result, Err(W3WError::Validation(_))));
+        assert_eq!(items[2].result.as_ref().unwrap().words, "filled.count.soap");
+    }
+
+    /// An `EventListener` which keeps the events it is told about.
+    struct EventLog(Arc<Mutex<Vec<String>>>);
+
+    impl EventListener for EventLog {
+        fn cache_hit(&self, cache: CacheKind) {
+            self.0.lock().unwrap().push(format!("hit {:?}", cache));
+        }
+
+        fn cache_miss(&self, cache: CacheKind) {
+            self.0.lock().unwrap().push(format!("miss {:?}", cache));
+        }
+
+        fn retry_scheduled(&self, attempt: u32, delay: Duration, error: &W3WError) {
+            self.0.lock().unwrap().push(format!(
+                "retry {} after {:?}: {}",
+                attempt,
+                delay,
+                error.is_transient()
+            ));
+        }
+
+        fn circuit_opened(&self, pause: Duration) {
+            self.0.lock().unwrap().push(format!("pause {:?}", pause));
+        }
+
+        fn quota_low(&self, keys_left: usize) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("quota low {}", keys_left));
+        }
+    }
+
+    #[test]
+    fn test_event_listener_is_told_about_cache_hits_and_misses() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let soap = square(51.520833, -0.195543);
+        let cache = AddressCache::new(10);
+        cache.insert(
+            String::from("format=json"),
+            address("filled.count.soap", soap),
+        );
+        w3_client.address_cache = Some(cache);
+        w3_client.autosuggest_cache = Some(AutosuggestCache::new(10));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        w3_client.event_listener = Some(Box::new(EventLog(Arc::clone(&events))));
+
+        let options = ConvertTo3WAOptions::default();
+        assert!(w3_client
+            .convert_to_3wa_typed(soap.center(), &options)
+            .is_ok());
+        assert!(w3_client
+            .convert_to_3wa_typed((51.6, -0.19), &options)
+            .is_err());
+        let options = ConvertToCoordinatesOptions::default();
+        assert!(w3_client
+            .convert_to_coordinates_typed("filled.count.soap", &options)
+            .is_ok());
+        assert!(w3_client
+            .autosuggest_typed("filled.count.so", &AutoSuggestOptions::default())
+            .is_err());
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                "hit Address",
+                "miss Address",
+                "hit Address",
+                "miss Autosuggest"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_listener_is_told_about_quota_and_retries() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let body = include_str!("../tests/fixtures/convert_to_coordinates.json");
+            for status in [
+                "402 Payment Required",
+                "402 Payment Required",
+                "503 Service Unavailable",
+                "200 OK",
+            ] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0; 4096];
+                let _ = stream.read(&mut request).unwrap();
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = format!("http://127.0.0.1:{}", port);
+        w3_client.key_pool = Some(KeyPool::new(["key-a", "key-b"]));
+        w3_client.quota_pause = Some(Duration::from_millis(20));
+        let events = Arc::new(Mutex::new(Vec::new()));
+        w3_client.event_listener = Some(Box::new(EventLog(Arc::clone(&events))));
+
+        let results = w3_client.convert_to_coordinates_bulk(
+            ["filled.count.soap"],
+            &ConvertToCoordinatesOptions::default(),
+            1,
+        );
+        server.join().unwrap();
+        assert!(results[0].is_ok());
+        assert_eq!(
+            *events.lock().unwrap(),
+            [
+                "quota low 1",
+                "quota low 0",
+                "pause 20ms",
+                "retry 1 after 500ms: true"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distance_between_3wa_uses_the_address_cache() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let cache = AddressCache::new(10);
+        cache.insert(
+            String::from("language=en&format=json"),
+            address("filled.count.soap", square(51.520833, -0.195543)),
+        );
+        w3_client.address_cache = Some(cache);
+
+        assert!(matches!(
+            w3_client.distance_between_3wa("filled.count.soap", "index.home.raft"),
+            Err(W3WError::Validation(_))
+        ));
+        w3_client.address_cache.as_ref().unwrap().insert(
+            String::from("language=en&format=json"),
+            address("index.home.raft", square(51.521251, -0.203586)),
+        );
+        let distance_m = w3_client
+            .distance_between_3wa("///Filled.Count.Soap", "index.home.raft")
+            .unwrap();
+        assert!((distance_m - 558.0).abs() < 1.0, "{}", distance_m);
+    }
+
+    #[test]
+    fn test_suggest_correction() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let cache = AutosuggestCache::new(10);
+        cache.insert(String::from("input=filled.count.soap"), Vec::new());
+        w3_client.autosuggest_cache = Some(cache);
+
+        assert_eq!(
+            w3_client.suggest_correction("12 Main Street").unwrap(),
+            Correction::NotAnAddress
+        );
         assert_eq!(
-            url.to_string(),
-            "https://test.com/endpoint?key=mock-api-key&language=nl&prefer-land=false"
+            w3_client.suggest_correction("Filled, count, soap").unwrap(),
+            Correction::NotAnAddress
         );
+        assert!(matches!(
+            w3_client.suggest_correction("index.home.raft"),
+            Err(W3WError::Validation(_))
+        ));
     }
 }
@@ -0,0 +1,29 @@
+//! The `Location` is the result of `W3WClient::resolve`, which accepts any kind of location
+//! input. Its `source` tells how the input was recognized.
+
+use crate::coordinate::Coordinate;
+
+/// A location resolved from user input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Location {
+    /// The coordinate of the location
+    pub coordinate: Coordinate,
+    /// The three word address, if the location was resolved through the what3words API
+    pub words: Option<String>,
+    /// How the input was recognized
+    pub source: LocationSource,
+}
+
+/// The kind of input a `Location` was resolved from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocationSource {
+    /// A three word address, converted with `convert-to-coordinates`
+    ThreeWordAddress,
+    /// A `latitude,longitude` pair or a coordinate in degrees, minutes and seconds or in the
+    /// ISO 6709 format, used as is
+    Coordinates,
+    /// A full plus code, decoded offline to the center of its area
+    PlusCode,
+    /// Free text, resolved to the best suggestion of autosuggest
+    Autosuggest,
+}
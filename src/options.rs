@@ -1,80 +1,353 @@
 //! Here are all `Options` structs defined which can be used to pass to what3words endpoints.
+//!
+//! Every options struct implements `Serialize`, so its fields can be turned into query
+//! parameters. Fields which are `None` are left out of the query string.
+//!
+//! The `DefaultOptions` of a client are merged into the options of every call: a field set in
+//! the call's options wins, an unset field falls back to the client default.
+//!
+//! The `headers` of an options struct are not part of the query: they are sent as extra HTTP
+//! headers with the call, on top of the `default_headers` of the client.
+
+use std::fmt::Display;
+
+use reqwest::header::HeaderMap;
+use serde::{Serialize, Serializer};
 
 use crate::bounding_box::BoundingBox;
 use crate::circle::Circle;
 use crate::coordinate::Coordinate;
+use crate::country_code::CountryCode;
+use crate::error::W3WError;
 use crate::polygon::Polygon;
 
+/// The largest number of suggestions the what3words API returns for one autosuggest call.
+const MAX_RESULTS: u8 = 100;
+
+/// The format of the payload returned by the what3words API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResponseFormat {
+    /// Plain JSON, which the what3words API uses when no format is given
+    Json,
+    /// A GeoJSON `FeatureCollection`
+    GeoJson,
+}
+
+/// The kind of input given to autosuggest. Voice input is sent as it came out of the speech
+/// recognition, without normalizing it to a 3word address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InputType {
+    /// Typed text, which the what3words API uses when no input type is given
+    #[default]
+    Text,
+    /// The output of the VoCon Hybrid speech recognition
+    VoconHybrid,
+    /// The output of the Nuance NMDP ASR speech recognition
+    NmdpAsr,
+    /// The output of any other speech recognition, with the words separated by spaces
+    GenericVoice,
+}
+
+/// The kind of autosuggest input a selection was made from, reported by
+/// `W3WClient::autosuggest_selection`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceApi {
+    /// The suggestions were made for typed text
+    Text,
+    /// The suggestions were made for voice input
+    Voice,
+}
+
+impl SourceApi {
+    /// Return the value of the `source-api` query parameter.
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            SourceApi::Text => "text",
+            SourceApi::Voice => "voice",
+        }
+    }
+}
+
 /// The optional parameters for the `convert_to_3wa` calls.
-#[derive(Debug)]
+#[derive(Debug, Default, Serialize)]
 pub struct ConvertTo3WAOptions<'a> {
     /// language of the returned 3 words
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub language: Option<&'a str>,
-    /// format of the returned payload. Either `"json"` or `"geojson"`
-    pub format: Option<&'a str>,
+    /// format of the returned payload
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ResponseFormat>,
     /// locale to specify a variant of a language
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub locale: Option<&'a str>,
+    /// extra HTTP headers sent with this call only
+    #[serde(skip)]
+    pub headers: Option<&'a HeaderMap>,
 }
 
-impl Default for ConvertTo3WAOptions<'_> {
-    fn default() -> Self {
-        ConvertTo3WAOptions {
-            language: None,
-            format: None,
-            locale: None,
-        }
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Default, Serialize)]
 pub struct ConvertToCoordinatesOptions<'a> {
-    pub format: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ResponseFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub locale: Option<&'a str>,
+    #[serde(skip)]
+    pub headers: Option<&'a HeaderMap>,
 }
 
-impl Default for ConvertToCoordinatesOptions<'_> {
-    fn default() -> Self {
-        ConvertToCoordinatesOptions {
-            format: None,
-            locale: None,
-        }
-    }
-}
-
-#[derive(Debug)]
+#[derive(Debug, Default, Serialize)]
 pub struct AutoSuggestOptions<'a> {
+    #[serde(
+        rename = "focus",
+        serialize_with = "serialize_display",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub focus_coordinates: Option<&'a Coordinate>,
-    pub circle: Option<&'a Circle<'a>>,
+    #[serde(
+        rename = "clip-to-circle",
+        serialize_with = "serialize_display",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub circle: Option<&'a Circle>,
+    #[serde(
+        rename = "clip-to-country",
+        serialize_with = "serialize_country_codes",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub countries: Option<&'a Vec<&'a str>>,
-    pub bounding_box: Option<&'a BoundingBox<'a>>,
-    pub polygon: Option<&'a Polygon<'a>>,
+    #[serde(
+        rename = "clip-to-bounding-box",
+        serialize_with = "serialize_display",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bounding_box: Option<&'a BoundingBox>,
+    #[serde(
+        rename = "clip-to-polygon",
+        serialize_with = "serialize_display",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub polygon: Option<&'a Polygon>,
+    /// whether a `polygon` beyond the limit of the what3words API is simplified with
+    /// `Polygon::simplified` instead of rejected, which defaults to `false`
+    #[serde(skip)]
+    pub simplify_polygon: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub language: Option<&'a str>,
+    #[serde(rename = "prefer-land", skip_serializing_if = "Option::is_none")]
     pub prefer_land: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub locale: Option<&'a str>,
+    /// number of suggestions to return, from 1 to 100
+    #[serde(rename = "n-results", skip_serializing_if = "Option::is_none")]
+    pub n_results: Option<u8>,
+    /// number of suggestions which are sorted by distance to the focus, at most `n_results`
+    #[serde(rename = "n-focus-results", skip_serializing_if = "Option::is_none")]
+    pub n_focus_results: Option<u8>,
+    /// kind of input, a voice input type requires a `language`
+    #[serde(rename = "input-type", skip_serializing_if = "Option::is_none")]
+    pub input_type: Option<InputType>,
+    #[serde(skip)]
+    pub headers: Option<&'a HeaderMap>,
 }
 
-impl Default for AutoSuggestOptions<'_> {
-    fn default() -> Self {
-        AutoSuggestOptions {
-            focus_coordinates: None,
-            circle: None,
-            countries: None,
-            bounding_box: None,
-            polygon: None,
-            language: None,
-            prefer_land: None,
-            locale: None,
+#[derive(Debug, Default, Serialize)]
+pub struct GridSectionOptions<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<ResponseFormat>,
+    #[serde(skip)]
+    pub headers: Option<&'a HeaderMap>,
+}
+
+/// The options of a call: the query parameters through `Serialize`, and the extra headers.
+pub(crate) trait RequestOptions: Serialize {
+    /// Return the extra HTTP headers of the call, if any.
+    fn headers(&self) -> Option<&HeaderMap>;
+}
+
+impl RequestOptions for () {
+    fn headers(&self) -> Option<&HeaderMap> {
+        None
+    }
+}
+
+impl RequestOptions for ConvertTo3WAOptions<'_> {
+    fn headers(&self) -> Option<&HeaderMap> {
+        self.headers
+    }
+}
+
+impl RequestOptions for ConvertToCoordinatesOptions<'_> {
+    fn headers(&self) -> Option<&HeaderMap> {
+        self.headers
+    }
+}
+
+impl RequestOptions for AutoSuggestOptions<'_> {
+    fn headers(&self) -> Option<&HeaderMap> {
+        self.headers
+    }
+}
+
+impl RequestOptions for GridSectionOptions<'_> {
+    fn headers(&self) -> Option<&HeaderMap> {
+        self.headers
+    }
+}
+
+/// Serialize an optional value through its `Display` implementation, which holds the
+/// format the what3words API expects for coordinates and shapes.
+fn serialize_display<T: Display, S: Serializer>(
+    value: &Option<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => serializer.collect_str(value),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Serialize an optional list of country codes as a single comma-separated string of uppercase
+/// codes. The codes are checked by `AutoSuggestOptions::validate` before.
+fn serialize_country_codes<S: Serializer>(
+    value: &Option<&Vec<&str>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(values) => serializer.serialize_str(&values.join(",").to_ascii_uppercase()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Options which apply to every call of a `W3WClient`, unless the options of the call itself
+/// set them. Each field is only used by the endpoints which support the parameter.
+#[derive(Debug, Clone, Default)]
+pub struct DefaultOptions {
+    /// language of the returned 3 words
+    pub language: Option<String>,
+    /// locale to specify a variant of a language
+    pub locale: Option<String>,
+    /// format of the returned payload
+    pub format: Option<ResponseFormat>,
+    /// circle to which autosuggest results are clipped
+    pub circle: Option<Circle>,
+    /// countries to which autosuggest results are clipped
+    pub countries: Option<Vec<String>>,
+    /// bounding box to which autosuggest results are clipped
+    pub bounding_box: Option<BoundingBox>,
+    /// polygon to which autosuggest results are clipped
+    pub polygon: Option<Polygon>,
+}
+
+impl DefaultOptions {
+    /// Return the default countries in the shape of `AutoSuggestOptions::countries`.
+    pub(crate) fn country_list(&self) -> Option<Vec<&str>> {
+        self.countries
+            .as_ref()
+            .map(|countries| countries.iter().map(String::as_str).collect())
+    }
+}
+
+impl<'a> ConvertTo3WAOptions<'a> {
+    /// Return these options with the unset fields taken from `defaults`.
+    pub(crate) fn or_defaults(&self, defaults: &'a DefaultOptions) -> ConvertTo3WAOptions<'a> {
+        ConvertTo3WAOptions {
+            language: self.language.or(defaults.language.as_deref()),
+            format: self.format.or(defaults.format),
+            locale: self.locale.or(defaults.locale.as_deref()),
+            headers: self.headers,
         }
     }
 }
 
-#[derive(Debug)]
-pub struct GridSectionOptions<'a> {
-    pub format: Option<&'a str>,
+impl<'a> ConvertToCoordinatesOptions<'a> {
+    /// Return these options with the unset fields taken from `defaults`.
+    pub(crate) fn or_defaults(
+        &self,
+        defaults: &'a DefaultOptions,
+    ) -> ConvertToCoordinatesOptions<'a> {
+        ConvertToCoordinatesOptions {
+            format: self.format.or(defaults.format),
+            locale: self.locale.or(defaults.locale.as_deref()),
+            headers: self.headers,
+        }
+    }
 }
 
-impl Default for GridSectionOptions<'_> {
-    fn default() -> Self {
-        GridSectionOptions { format: None }
+impl<'a> AutoSuggestOptions<'a> {
+    /// Return these options with the unset fields taken from `defaults`. The default countries
+    /// are passed separately as `DefaultOptions::country_list`, as they are stored as `String`s.
+    pub(crate) fn or_defaults(
+        &self,
+        defaults: &'a DefaultOptions,
+        default_countries: Option<&'a Vec<&'a str>>,
+    ) -> AutoSuggestOptions<'a> {
+        AutoSuggestOptions {
+            circle: self.circle.or(defaults.circle.as_ref()),
+            countries: self.countries.or(default_countries),
+            bounding_box: self.bounding_box.or(defaults.bounding_box.as_ref()),
+            polygon: self.polygon.or(defaults.polygon.as_ref()),
+            language: self.language.or(defaults.language.as_deref()),
+            locale: self.locale.or(defaults.locale.as_deref()),
+            ..*self
+        }
+    }
+
+    /// Whether the input is the output of a speech recognition rather than typed text.
+    pub(crate) fn is_voice(&self) -> bool {
+        !matches!(self.input_type, None | Some(InputType::Text))
+    }
+
+    /// Check that the numbers of results are within the range the what3words API accepts, that
+    /// all countries are two letter codes, that the clipping shapes are valid and that voice
+    /// input comes with a language.
+    pub(crate) fn validate(&self) -> Result<(), W3WError> {
+        for (name, value) in [
+            ("n-results", self.n_results),
+            ("n-focus-results", self.n_focus_results),
+        ] {
+            if let Some(value) = value {
+                if !(1..=MAX_RESULTS).contains(&value) {
+                    return Err(W3WError::Validation(format!(
+                        "{} must be between 1 and {}, got {}",
+                        name, MAX_RESULTS, value
+                    )));
+                }
+            }
+        }
+        if let (Some(n_results), Some(n_focus_results)) = (self.n_results, self.n_focus_results) {
+            if n_focus_results > n_results {
+                return Err(W3WError::Validation(format!(
+                    "n-focus-results ({}) cannot be larger than n-results ({})",
+                    n_focus_results, n_results
+                )));
+            }
+        }
+        for country in self.countries.into_iter().flatten() {
+            country.parse::<CountryCode>()?;
+        }
+        if let Some(bounding_box) = self.bounding_box {
+            bounding_box.validate()?;
+        }
+        if let Some(polygon) = self.polygon {
+            polygon.validate()?;
+        }
+        if self.is_voice() && self.language.is_none() {
+            return Err(W3WError::Validation(String::from(
+                "a language is required for voice input",
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl<'a> GridSectionOptions<'a> {
+    /// Return these options with the unset fields taken from `defaults`.
+    pub(crate) fn or_defaults(&self, defaults: &DefaultOptions) -> GridSectionOptions<'a> {
+        GridSectionOptions {
+            format: self.format.or(defaults.format),
+            headers: self.headers,
+        }
     }
 }
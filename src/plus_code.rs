@@ -0,0 +1,94 @@
+//! Offline decoding of full plus codes (Open Location Codes), e.g. `9C3XGV4C+XV`, used to
+//! recognize them in `W3WClient::resolve`. Short codes like `GV4C+XV London` need a reference
+//! location and are not decoded.
+
+use crate::coordinate::Coordinate;
+
+/// The digits of a plus code, in order of their value.
+const ALPHABET: &[u8] = b"23456789CFGHJMPQRVWX";
+/// The number of digits before the `+` of a full code.
+const SEPARATOR_POSITION: usize = 8;
+/// The number of digits which are decoded in latitude and longitude pairs.
+const PAIR_DIGITS: usize = 10;
+
+/// Decode a full plus code into the coordinate of the center of its area. Returns `None` for
+/// input which is not a full plus code.
+pub(crate) fn decode(code: &str) -> Option<Coordinate> {
+    let code = code.trim().to_ascii_uppercase();
+    let (before, after) = code.split_once('+')?;
+    if before.len() != SEPARATOR_POSITION || after.len() == 1 {
+        return None;
+    }
+    let digits = before.trim_end_matches('0');
+    if digits.len() % 2 == 1
+        || digits.contains('0')
+        || (digits.len() < before.len() && !after.is_empty())
+    {
+        return None;
+    }
+    let values: Vec<f64> = digits
+        .bytes()
+        .chain(after.bytes())
+        .map(|digit| {
+            ALPHABET
+                .iter()
+                .position(|&d| d == digit)
+                .map(|value| value as f64)
+        })
+        .collect::<Option<_>>()?;
+    if values.len() < 2 || values[0] >= 9.0 || values[1] >= 18.0 {
+        return None;
+    }
+
+    let (mut latitude, mut longitude) = (-90.0, -180.0);
+    let (mut lat_size, mut lng_size) = (400.0, 400.0);
+    for pair in values[..values.len().min(PAIR_DIGITS)].chunks(2) {
+        lat_size /= 20.0;
+        lng_size /= 20.0;
+        latitude += pair[0] * lat_size;
+        longitude += pair[1] * lng_size;
+    }
+    for &value in values.iter().skip(PAIR_DIGITS) {
+        lat_size /= 5.0;
+        lng_size /= 4.0;
+        latitude += (value / 4.0).floor() * lat_size;
+        longitude += (value % 4.0) * lng_size;
+    }
+    Some(Coordinate {
+        latitude: latitude + lat_size / 2.0,
+        longitude: longitude + lng_size / 2.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+
+    #[test]
+    fn test_decode_full_codes() {
+        let zurich = decode("8FVC9G8F+6X").unwrap();
+        assert!((zurich.latitude - 47.3655625).abs() < 1e-7);
+        assert!((zurich.longitude - 8.5249375).abs() < 1e-7);
+
+        let refined = decode("8fvc9g8f+6xq").unwrap();
+        assert!((refined.latitude - 47.3655875).abs() < 1e-7);
+
+        let padded = decode("8FVC0000+").unwrap();
+        assert!((padded.latitude - 47.5).abs() < 1e-9);
+        assert!((padded.longitude - 8.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_decode_rejects_other_input() {
+        for input in [
+            "GV4C+XV",
+            "8FVC9G8F+6",
+            "8FVC00F0+",
+            "8FVC0000+6X",
+            "8FVC9G8F+6Z",
+            "filled.count.soap",
+        ] {
+            assert_eq!(decode(input), None, "{}", input);
+        }
+    }
+}
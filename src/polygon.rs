@@ -1,25 +1,274 @@
 //! A Polygon is a figure defined by multiple coordinates and can be used in certain what3words API
 //! calls.
 
+use std::fmt;
+
 use crate::coordinate::Coordinate;
+use crate::error::W3WError;
+use crate::geo_utils::EARTH_RADIUS_M;
+
+/// The largest number of coordinate pairs of a polygon which the what3words API accepts,
+/// including the first coordinate which is repeated to close the ring.
+pub(crate) const MAX_POLYGON_PAIRS: usize = 25;
 
 /// A polygon defined by at least 3 coordinates. The what3words API only supports up to 25
-/// coordinates at the moment.
-#[derive(Debug)]
-pub struct Polygon<'a> {
+/// coordinate pairs at the moment, including the repeated first coordinate, so at most 24
+/// distinct coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polygon {
     /// Vector of the coordinates of the polygon
-    pub coordinates: Vec<&'a Coordinate>,
+    pub coordinates: Vec<Coordinate>,
+}
+
+impl Polygon {
+    /// Creates a polygon after checking its coordinates: there must be at least 3 of them, at
+    /// most 24 so the closed ring fits the limit of 25 pairs, and each must be valid, see
+    /// `Coordinate::new`. A ring which is already closed, with the first coordinate repeated
+    /// as the last, is accepted and stored open. The coordinates may also be tuples or arrays,
+    /// see `Coordinate::from`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// let polygon = Polygon::new(vec![
+    ///     Coordinate::new(51.521, -0.343)?,
+    ///     Coordinate::new(52.6, 2.3324)?,
+    ///     Coordinate::new(54.234, 8.343)?,
+    /// ])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new(
+        coordinates: impl IntoIterator<Item = impl Into<Coordinate>>,
+    ) -> Result<Polygon, W3WError> {
+        let mut coordinates: Vec<Coordinate> = coordinates.into_iter().map(Into::into).collect();
+        if coordinates.len() > 1 && coordinates.first() == coordinates.last() {
+            coordinates.pop();
+        }
+        let polygon = Polygon { coordinates };
+        polygon.validate()?;
+        Ok(polygon)
+    }
+
+    /// Return whether the coordinate lies within the polygon, by casting a ray from the
+    /// coordinate and counting the edges it crosses. Latitude and longitude are treated as
+    /// plane coordinates, which is accurate for polygons of a city or region.
+    pub fn contains(&self, coordinate: &Coordinate) -> bool {
+        let (x, y) = (coordinate.longitude, coordinate.latitude);
+        let mut inside = false;
+        let mut previous = match self.coordinates.last() {
+            Some(last) => last,
+            None => return false,
+        };
+        for current in &self.coordinates {
+            let (x1, y1) = (current.longitude, current.latitude);
+            let (x2, y2) = (previous.longitude, previous.latitude);
+            if (y1 > y) != (y2 > y) && x < (x2 - x1) * (y - y1) / (y2 - y1) + x1 {
+                inside = !inside;
+            }
+            previous = current;
+        }
+        inside
+    }
+
+    /// Return the centroid of the area of the polygon, e.g. to pick the autosuggest focus for a
+    /// delivery zone, or `None` for a polygon without coordinates. Latitude and longitude are
+    /// treated as plane coordinates, like `contains`. A polygon without area returns the mean
+    /// of its coordinates.
+    pub fn centroid(&self) -> Option<Coordinate> {
+        let ring = self.ring();
+        let first = ring.first()?;
+        let doubled_area = 2.0 * signed_area(ring);
+        if doubled_area == 0.0 {
+            let count = ring.len() as f64;
+            return Some(Coordinate {
+                latitude: ring.iter().map(|c| c.latitude).sum::<f64>() / count,
+                longitude: ring.iter().map(|c| c.longitude).sum::<f64>() / count,
+            });
+        }
+        // relative to the first coordinate, which keeps the products small and precise
+        let (mut x, mut y) = (0.0, 0.0);
+        let mut previous = ring[ring.len() - 1];
+        for current in ring {
+            let (x1, y1) = (
+                previous.longitude - first.longitude,
+                previous.latitude - first.latitude,
+            );
+            let (x2, y2) = (
+                current.longitude - first.longitude,
+                current.latitude - first.latitude,
+            );
+            let cross = x1 * y2 - x2 * y1;
+            x += (x1 + x2) * cross;
+            y += (y1 + y2) * cross;
+            previous = *current;
+        }
+        Some(Coordinate {
+            latitude: first.latitude + y / (3.0 * doubled_area),
+            longitude: first.longitude + x / (3.0 * doubled_area),
+        })
+    }
+
+    /// Return the area of the polygon on the ground in square meters, calculated on a
+    /// spherical earth, which is accurate within about 0.5 %.
+    pub fn area_m2(&self) -> f64 {
+        let ring = self.ring();
+        let mut previous = match ring.last() {
+            Some(last) => last,
+            None => return 0.0,
+        };
+        let mut sum = 0.0;
+        for current in ring {
+            sum += (current.longitude - previous.longitude).to_radians()
+                * (2.0
+                    + previous.latitude.to_radians().sin()
+                    + current.latitude.to_radians().sin());
+            previous = current;
+        }
+        (sum * EARTH_RADIUS_M * EARTH_RADIUS_M / 2.0).abs()
+    }
+
+    /// Return the polygon as Well-Known Text, e.g. `POLYGON((4 51, 5 51, 5 52, 4 51))` with the
+    /// longitude first and the ring closed, for spatial databases and QGIS. A polygon without
+    /// coordinates is `POLYGON EMPTY`.
+    pub fn to_wkt(&self) -> String {
+        let ring = self.ring();
+        match ring.first() {
+            Some(first) => {
+                let points: Vec<String> = ring.iter().chain([first]).map(wkt_point).collect();
+                format!("POLYGON(({}))", points.join(", "))
+            }
+            None => String::from("POLYGON EMPTY"),
+        }
+    }
+
+    /// Return the polygon with a consistent ring: repeated coordinates, including the closing
+    /// coordinate, are removed and the coordinates run counter-clockwise, as GeoJSON prescribes
+    /// for an outer ring. The first coordinate stays first. Autosuggest normalizes its
+    /// `polygon` this way before sending it, so the same area is always sent the same way.
+    pub fn normalized(&self) -> Polygon {
+        let mut coordinates = self.ring().to_vec();
+        coordinates.dedup();
+        if coordinates.len() > 1 && coordinates.first() == coordinates.last() {
+            coordinates.pop();
+        }
+        if signed_area(&coordinates) < 0.0 {
+            coordinates[1..].reverse();
+        }
+        Polygon { coordinates }
+    }
+
+    /// Return the polygon simplified to at most `max_coordinates` coordinates, e.g. to fit a
+    /// polygon traced from GeoJSON into the 24 coordinates of a `clip-to-polygon`. It uses the
+    /// Visvalingam-Whyatt algorithm, which drops the coordinate forming the smallest triangle
+    /// with its neighbors until few enough are left, so the shape changes as little as
+    /// possible. At least 3 coordinates are kept and a closed ring is returned open.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// # fn main() -> Result<(), what3words::W3WError> {
+    /// # let traced = Polygon::new(vec![Coordinate { latitude: 51.521, longitude: -0.343 }, Coordinate { latitude: 52.6, longitude: 2.3324 }, Coordinate { latitude: 54.234, longitude: 8.343 }])?;
+    /// let clip = traced.simplified(24);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn simplified(&self, max_coordinates: usize) -> Polygon {
+        let mut coordinates = self.ring().to_vec();
+        let area = |coordinates: &[Coordinate], i: usize| {
+            let n = coordinates.len();
+            let (a, b, c) = (
+                coordinates[(i + n - 1) % n],
+                coordinates[i],
+                coordinates[(i + 1) % n],
+            );
+            ((b.longitude - a.longitude) * (c.latitude - a.latitude)
+                - (c.longitude - a.longitude) * (b.latitude - a.latitude))
+                .abs()
+        };
+        while coordinates.len() > max_coordinates.max(3) {
+            let smallest = (0..coordinates.len())
+                .min_by(|&i, &j| area(&coordinates, i).total_cmp(&area(&coordinates, j)))
+                .unwrap_or_default();
+            coordinates.remove(smallest);
+        }
+        Polygon { coordinates }
+    }
+
+    /// Return whether the closed ring has more coordinate pairs than the what3words API
+    /// accepts.
+    pub(crate) fn exceeds_limit(&self) -> bool {
+        self.ring().len() + 1 > MAX_POLYGON_PAIRS
+    }
+
+    /// Return the coordinates without the last one when it repeats the first.
+    fn ring(&self) -> &[Coordinate] {
+        match self.coordinates.as_slice() {
+            [first, ring @ .., last] if !ring.is_empty() && first == last => {
+                &self.coordinates[..self.coordinates.len() - 1]
+            }
+            coordinates => coordinates,
+        }
+    }
+
+    /// Check the number of coordinates and each coordinate, which the what3words API would
+    /// otherwise report as an error.
+    pub(crate) fn validate(&self) -> Result<(), W3WError> {
+        let ring = self.ring();
+        if ring.len() < 3 {
+            return Err(W3WError::Validation(format!(
+                "a polygon needs at least 3 distinct coordinates, got {}",
+                ring.len()
+            )));
+        }
+        if self.exceeds_limit() {
+            return Err(W3WError::Validation(format!(
+                "a polygon is limited to {} coordinate pairs including the repeated first \
+                 coordinate, got {}",
+                MAX_POLYGON_PAIRS,
+                ring.len() + 1
+            )));
+        }
+        ring.iter().try_for_each(Coordinate::validate)
+    }
+}
+
+/// Format a coordinate as the `<longitude> <latitude>` of a Well-Known Text point.
+pub(crate) fn wkt_point(coordinate: &Coordinate) -> String {
+    format!("{} {}", coordinate.longitude, coordinate.latitude)
+}
+
+/// Return the area enclosed by the coordinates in square degrees, positive when they run
+/// counter-clockwise, with the longitude as `x` and the latitude as `y`.
+fn signed_area(coordinates: &[Coordinate]) -> f64 {
+    let mut previous = match coordinates.last() {
+        Some(last) => last,
+        None => return 0.0,
+    };
+    let mut doubled_area = 0.0;
+    for current in coordinates {
+        doubled_area +=
+            previous.longitude * current.latitude - current.longitude * previous.latitude;
+        previous = current;
+    }
+    doubled_area / 2.0
 }
 
-impl Polygon<'_> {
-    /// Returns a string of all the coordinates of the polygon separated with a comma. As last
-    /// element, the first coordinate is added again as per the what3words API documentation.
-    pub fn to_string(&self) -> String {
-        let mut url: String = String::new();
-        for item in self.coordinates.iter() {
-            url.push_str(&format!("{},", &item.to_string()));
+impl fmt::Display for Polygon {
+    /// Format all the coordinates of the polygon separated with a comma. As last
+    /// element, the first coordinate is added again as per the what3words API documentation,
+    /// unless the ring is already closed. An empty polygon is formatted as an empty string.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ring = self.ring();
+        if let Some(first) = ring.first() {
+            for item in ring.iter() {
+                write!(f, "{},", item)?;
+            }
+            write!(f, "{}", first)?;
         }
-        url.push_str(&self.coordinates[0].to_string());
-        url
+        Ok(())
     }
 }
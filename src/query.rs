@@ -0,0 +1,115 @@
+//! The query parameters of a request are added with `RequestBuilder::query`, straight from the
+//! required parameters of an endpoint and its `Serialize` options. `query_string` formats the
+//! same parameters, percent-encoded, as the key of a cache.
+//!
+//! A parameter may be given once only: `check_distinct_names` reports a name which is given
+//! twice as a validation error before the request is sent, so an option can never silently
+//! shadow a required parameter like `input` or `key`. Conflicts between the client defaults and
+//! the options of a call are resolved before that, by `or_defaults`: the options always win.
+
+use serde::Serialize;
+
+use crate::error::W3WError;
+
+/// Return the parameters followed by the fields of the options as a percent-encoded query
+/// string, in the order of `params` and the order of the fields. Unset fields are left out.
+pub(crate) fn query_string<T: Serialize>(
+    params: &[(&str, &str)],
+    options: &T,
+) -> Result<String, W3WError> {
+    let params = serde_urlencoded::to_string(params).map_err(unserializable)?;
+    let options = serde_urlencoded::to_string(options).map_err(unserializable)?;
+    Ok(match (params.is_empty(), options.is_empty()) {
+        (_, true) => params,
+        (true, false) => options,
+        (false, false) => format!("{}&{}", params, options),
+    })
+}
+
+/// Check that every name among `params` and the fields of the options is given once, or return
+/// a validation error naming the first one which is given twice.
+pub(crate) fn check_distinct_names<T: Serialize>(
+    params: &[(&str, &str)],
+    options: &T,
+) -> Result<(), W3WError> {
+    let options = serde_urlencoded::to_string(options).map_err(unserializable)?;
+    let mut names: Vec<&str> = params.iter().map(|(name, _)| *name).collect();
+    for pair in options.split('&').filter(|pair| !pair.is_empty()) {
+        names.push(pair.split('=').next().unwrap_or(pair));
+    }
+    for (index, name) in names.iter().enumerate() {
+        if names[..index].contains(name) {
+            return Err(W3WError::Validation(format!(
+                "the query parameter '{}' is given more than once",
+                name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Return the error for options which cannot be serialized as query parameters.
+fn unserializable(error: serde_urlencoded::ser::Error) -> W3WError {
+    W3WError::Validation(format!(
+        "the options cannot be serialized as query parameters: {}",
+        error
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_distinct_names, query_string};
+    use crate::{
+        AutoSuggestOptions, ConvertTo3WAOptions, DefaultOptions, ResponseFormat, W3WError,
+    };
+
+    #[test]
+    fn test_duplicate_parameters_are_rejected() {
+        let options = ConvertTo3WAOptions {
+            language: Some("nl"),
+            ..Default::default()
+        };
+        assert!(check_distinct_names(&[("coordinates", "51,4")], &options).is_ok());
+        assert!(matches!(
+            check_distinct_names(&[("language", "fr")], &options),
+            Err(W3WError::Validation(message)) if message.contains("'language'")
+        ));
+        assert!(matches!(
+            check_distinct_names(&[("key", "a"), ("key", "b")], &()),
+            Err(W3WError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_options_override_defaults_deterministically() {
+        let defaults = DefaultOptions {
+            language: Some(String::from("nl")),
+            locale: Some(String::from("nl_be")),
+            ..Default::default()
+        };
+        let options = AutoSuggestOptions {
+            language: Some("fr"),
+            prefer_land: Some(true),
+            ..Default::default()
+        };
+        let build = || {
+            query_string(
+                &[("input", "filled.count.so")],
+                &options.or_defaults(&defaults, None),
+            )
+            .unwrap()
+        };
+        assert_eq!(build(), build());
+        assert_eq!(
+            build(),
+            "input=filled.count.so&language=fr&prefer-land=true&locale=nl_be"
+        );
+
+        let options = ConvertTo3WAOptions {
+            format: Some(ResponseFormat::Json),
+            ..Default::default()
+        };
+        assert_eq!(query_string(&[], &options).unwrap(), "format=json");
+        assert_eq!(query_string(&[], &()).unwrap(), "");
+    }
+}
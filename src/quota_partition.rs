@@ -0,0 +1,196 @@
+//! A `QuotaPartition` splits the calls of a what3words plan between the tenants of a service, so
+//! one busy tenant cannot use up the quota of the others. Every tenant gets a share of the
+//! budget by weight, and a call of a tenant which used its share is rejected before it is sent.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use reqwest::StatusCode;
+
+use crate::error::{ErrorResponse, W3WError};
+
+/// A budget of calls, split between named tenants.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::*;
+/// # fn main() -> Result<(), what3words::W3WError> {
+/// # let w3_client = W3WClient::new("your_api_key");
+/// let partition = QuotaPartition::new(100_000, [("acme", 3), ("globex", 1)]);
+/// let address = partition.call("acme", || {
+///     w3_client.convert_to_3wa_typed((51.520847, -0.195521), &ConvertTo3WAOptions::default())
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct QuotaPartition {
+    tenants: Vec<Tenant>,
+}
+
+struct Tenant {
+    name: String,
+    share: u64,
+    used: AtomicU64,
+}
+
+/// The usage of a single tenant of a `QuotaPartition`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TenantUsage {
+    /// The name of the tenant
+    pub tenant: String,
+    /// The number of calls the tenant may make
+    pub share: u64,
+    /// The number of calls the tenant made
+    pub used: u64,
+}
+
+impl QuotaPartition {
+    /// Creates a partition of `budget` calls between the given tenants, each with a weight. A
+    /// tenant gets its weight divided by the total weight of all tenants of the budget, rounded
+    /// down, so the shares never exceed the budget.
+    pub fn new<T: Into<String>>(budget: u64, tenants: impl IntoIterator<Item = (T, u32)>) -> Self {
+        let tenants: Vec<(String, u32)> = tenants
+            .into_iter()
+            .map(|(name, weight)| (name.into(), weight))
+            .collect();
+        let total_weight: u128 = tenants.iter().map(|(_, weight)| u128::from(*weight)).sum();
+        QuotaPartition {
+            tenants: tenants
+                .into_iter()
+                .map(|(name, weight)| Tenant {
+                    name,
+                    share: (u128::from(budget) * u128::from(weight))
+                        .checked_div(total_weight)
+                        .unwrap_or_default() as u64,
+                    used: AtomicU64::new(0),
+                })
+                .collect(),
+        }
+    }
+
+    /// Count a call for `tenant` and make it with `f`. A call which fails with a
+    /// `W3WError::Validation` was not sent, so it is not counted.
+    ///
+    /// A tenant which used its share gets a `402 Payment Required` error without calling `f`,
+    /// like a key which is out of quota, so `W3WError::is_quota_exceeded` holds for it. As no
+    /// request is sent, its `url` is empty. An unknown tenant gets a `W3WError::Validation`.
+    pub fn call<R>(
+        &self,
+        tenant: &str,
+        f: impl FnOnce() -> Result<R, W3WError>,
+    ) -> Result<R, W3WError> {
+        let budget = self
+            .tenants
+            .iter()
+            .find(|budget| budget.name == tenant)
+            .ok_or_else(|| W3WError::Validation(format!("unknown tenant '{}'", tenant)))?;
+        let reserved = budget
+            .used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                (used < budget.share).then_some(used + 1)
+            });
+        if reserved.is_err() {
+            return Err(W3WError::Response(ErrorResponse {
+                status: StatusCode::PAYMENT_REQUIRED,
+                url: String::new(),
+                body: format!(
+                    "tenant '{}' used its share of {} calls",
+                    tenant, budget.share
+                ),
+            }));
+        }
+        let result = f();
+        if let Err(W3WError::Validation(_)) = result {
+            budget.used.fetch_sub(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    /// Return the number of calls `tenant` may still make, or `None` for an unknown tenant.
+    pub fn remaining(&self, tenant: &str) -> Option<u64> {
+        self.tenants
+            .iter()
+            .find(|budget| budget.name == tenant)
+            .map(|budget| {
+                budget
+                    .share
+                    .saturating_sub(budget.used.load(Ordering::Relaxed))
+            })
+    }
+
+    /// Clear the calls of all tenants, e.g. when a new quota period starts.
+    pub fn reset(&self) {
+        for tenant in &self.tenants {
+            tenant.used.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Return the usage of every tenant, in the order of the partition.
+    pub fn usage(&self) -> Vec<TenantUsage> {
+        self.tenants
+            .iter()
+            .map(|tenant| TenantUsage {
+                tenant: tenant.name.clone(),
+                share: tenant.share,
+                used: tenant.used.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for QuotaPartition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("QuotaPartition")
+            .field("usage", &self.usage())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QuotaPartition, TenantUsage};
+    use crate::error::W3WError;
+
+    #[test]
+    fn test_tenants_are_limited_to_their_share() {
+        let partition = QuotaPartition::new(10, [("acme", 3), ("globex", 1), ("initech", 0)]);
+        assert_eq!(partition.remaining("acme"), Some(7));
+        assert_eq!(partition.remaining("globex"), Some(2));
+        assert_eq!(partition.remaining("hooli"), None);
+
+        for _ in 0..2 {
+            assert_eq!(partition.call("globex", || Ok(1)).unwrap(), 1);
+        }
+        let error = partition.call("globex", || Ok(1)).unwrap_err();
+        assert!(error.is_quota_exceeded());
+        assert!(partition.call("initech", || Ok(1)).is_err());
+        assert_eq!(partition.call("acme", || Ok(1)).unwrap(), 1);
+        assert!(matches!(
+            partition.call("hooli", || Ok(1)),
+            Err(W3WError::Validation(_))
+        ));
+
+        let result: Result<(), W3WError> =
+            partition.call("acme", || Err(W3WError::Validation(String::from("bad"))));
+        assert!(result.is_err());
+        assert_eq!(
+            partition.usage()[..2],
+            [
+                TenantUsage {
+                    tenant: String::from("acme"),
+                    share: 7,
+                    used: 1
+                },
+                TenantUsage {
+                    tenant: String::from("globex"),
+                    share: 2,
+                    used: 2
+                }
+            ]
+        );
+
+        partition.reset();
+        assert_eq!(partition.remaining("globex"), Some(2));
+    }
+}
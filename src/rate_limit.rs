@@ -0,0 +1,112 @@
+//! A `RateLimit` keeps the requests of a `W3WClient` within the requests per second or per
+//! minute of a what3words plan. It is a token bucket: a burst of up to the full allowance is
+//! sent at once, after which requests are spread evenly over the period.
+
+use std::fmt;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A limit of `requests` per `period`, shared by all threads which use the client.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::*;
+/// # fn main() -> Result<(), what3words::W3WError> {
+/// let mut w3_client = W3WClient::new("your_api_key");
+/// w3_client.rate_limit = Some(RateLimit::per_minute(600));
+/// # Ok(())
+/// # }
+/// ```
+pub struct RateLimit {
+    requests: u32,
+    period: Duration,
+    bucket: Mutex<Bucket>,
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimit {
+    /// Creates a limit of `requests` per `period`, which starts with a full bucket. A limit of
+    /// zero requests or an empty period is raised to one request per period, respectively one
+    /// millisecond.
+    pub fn new(requests: u32, period: Duration) -> Self {
+        let requests = requests.max(1);
+        RateLimit {
+            requests,
+            period: period.max(Duration::from_millis(1)),
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(requests),
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Creates a limit of `requests` per second.
+    pub fn per_second(requests: u32) -> Self {
+        RateLimit::new(requests, Duration::from_secs(1))
+    }
+
+    /// Creates a limit of `requests` per minute.
+    pub fn per_minute(requests: u32) -> Self {
+        RateLimit::new(requests, Duration::from_secs(60))
+    }
+
+    /// Block until a request may be sent, and count it.
+    pub(crate) fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self
+                    .bucket
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                let now = Instant::now();
+                let refill =
+                    now.duration_since(bucket.refilled_at).as_secs_f64() * self.tokens_per_second();
+                bucket.tokens = (bucket.tokens + refill).min(f64::from(self.requests));
+                bucket.refilled_at = now;
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - bucket.tokens) / self.tokens_per_second())
+            };
+            thread::sleep(wait);
+        }
+    }
+
+    fn tokens_per_second(&self) -> f64 {
+        f64::from(self.requests) / self.period.as_secs_f64()
+    }
+}
+
+impl fmt::Debug for RateLimit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RateLimit")
+            .field("requests", &self.requests)
+            .field("period", &self.period)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::RateLimit;
+
+    #[test]
+    fn test_rate_limit_allows_a_burst_and_then_waits() {
+        let rate_limit = RateLimit::new(2, Duration::from_millis(100));
+        let start = Instant::now();
+        rate_limit.acquire();
+        rate_limit.acquire();
+        assert!(start.elapsed() < Duration::from_millis(40));
+        rate_limit.acquire();
+        assert!(start.elapsed() >= Duration::from_millis(45));
+    }
+}
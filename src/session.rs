@@ -0,0 +1,434 @@
+//! The `AutosuggestSession` drives a search box on top of `autosuggest`: it skips requests
+//! while the user is still typing, keeps the focus up to date and reports the selection once
+//! the user picks a suggestion. A `SelectionRecorder` set on the session keeps every selection
+//! for analytics, e.g. as JSON lines with a `JsonlSelectionRecorder`.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::json;
+
+use crate::coordinate::Coordinate;
+use crate::error::W3WError;
+use crate::options::{AutoSuggestOptions, SourceApi};
+use crate::suggestion::Suggestion;
+use crate::W3WClient;
+
+/// The time between two requests of a session, unless set with `AutosuggestSession::debounce`.
+const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// The state of a single typeahead interaction, from the first keystroke to the selection.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::*;
+/// # fn main() -> Result<(), what3words::W3WError> {
+/// # let w3_client = W3WClient::new("your_api_key");
+/// # let current_location = Coordinate { latitude: 51.520847, longitude: -0.195521 };
+/// let mut session = AutosuggestSession::new(&w3_client, AutoSuggestOptions::default());
+/// session.set_focus(Some(current_location));
+/// for input in ["filled.count", "filled.count.s", "filled.count.so"] {
+///     if let Some(suggestions) = session.update(input)? {
+///         println!("{:?}", suggestions);
+///     }
+/// }
+/// let selected = session.flush()?[0].clone();
+/// session.select(&selected)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AutosuggestSession<'a> {
+    client: &'a W3WClient,
+    options: AutoSuggestOptions<'a>,
+    focus: Option<Coordinate>,
+    debounce: Duration,
+    input: String,
+    sent_input: Option<String>,
+    last_request: Option<Instant>,
+    suggestions: Vec<Suggestion>,
+    recorder: Option<&'a dyn SelectionRecorder>,
+}
+
+impl<'a> AutosuggestSession<'a> {
+    /// Creates a session which sends its requests with `client` and `options`.
+    pub fn new(client: &'a W3WClient, options: AutoSuggestOptions<'a>) -> Self {
+        AutosuggestSession {
+            client,
+            options,
+            focus: None,
+            debounce: DEFAULT_DEBOUNCE,
+            input: String::new(),
+            sent_input: None,
+            last_request: None,
+            suggestions: Vec::new(),
+            recorder: None,
+        }
+    }
+
+    /// Set the minimum time between two requests, which defaults to 250 ms.
+    pub fn debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Record the selection of the session with `recorder`, next to reporting it to the API.
+    pub fn recorder(mut self, recorder: &'a dyn SelectionRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Set the focus of the next requests, e.g. when the location of the user moves. It takes
+    /// precedence over the focus in the options of the session.
+    pub fn set_focus(&mut self, focus: Option<Coordinate>) {
+        self.focus = focus;
+    }
+
+    /// Update the input of the search box. The suggestions are only requested when the last
+    /// request is at least the debounce time ago; otherwise `None` is returned and the input
+    /// is kept until the next `update` or `flush`. Input which was already requested returns
+    /// the suggestions of that request.
+    pub fn update(&mut self, input: &str) -> Result<Option<&[Suggestion]>, W3WError> {
+        input.clone_into(&mut self.input);
+        if self.sent_input.as_deref() == Some(input) {
+            return Ok(Some(&self.suggestions));
+        }
+        if let Some(last_request) = self.last_request {
+            if last_request.elapsed() < self.debounce {
+                return Ok(None);
+            }
+        }
+        self.request()?;
+        Ok(Some(&self.suggestions))
+    }
+
+    /// Request the suggestions for the latest input, ignoring the debounce time, e.g. when the
+    /// user stops typing.
+    pub fn flush(&mut self) -> Result<&[Suggestion], W3WError> {
+        if self.sent_input.as_deref() != Some(self.input.as_str()) {
+            self.request()?;
+        }
+        Ok(&self.suggestions)
+    }
+
+    /// Report the suggestion the user selected for the latest input, which ends the session.
+    /// The selection is recorded before it is reported, so it is also recorded when the report
+    /// fails.
+    pub fn select(self, suggestion: &Suggestion) -> Result<(), W3WError> {
+        if let Some(recorder) = self.recorder {
+            recorder.record(&Selection {
+                input: &self.input,
+                suggestions: &self.suggestions,
+                selected: suggestion,
+                timestamp: SystemTime::now(),
+            });
+        }
+        let source_api = if self.options.is_voice() {
+            SourceApi::Voice
+        } else {
+            SourceApi::Text
+        };
+        self.client.autosuggest_selection(
+            &self.input,
+            &suggestion.words,
+            suggestion.rank,
+            source_api,
+        )
+    }
+
+    /// Request the suggestions for the latest input. The suggestions of an earlier input are
+    /// dropped, also when the request fails, so they are never shown for the new input.
+    fn request(&mut self) -> Result<(), W3WError> {
+        self.last_request = Some(Instant::now());
+        self.sent_input = None;
+        self.suggestions.clear();
+        let options = AutoSuggestOptions {
+            focus_coordinates: self.focus.as_ref().or(self.options.focus_coordinates),
+            ..self.options
+        };
+        self.suggestions = self.client.autosuggest_typed(&self.input, &options)?;
+        self.sent_input = Some(self.input.clone());
+        Ok(())
+    }
+}
+
+/// A suggestion the user selected in an `AutosuggestSession`, with what the user saw.
+#[derive(Debug, Clone, Copy)]
+pub struct Selection<'s> {
+    /// The latest input of the session, which is the input reported to the API
+    pub input: &'s str,
+    /// The suggestions shown for the last requested input, in the order they were shown
+    pub suggestions: &'s [Suggestion],
+    /// The suggestion the user selected
+    pub selected: &'s Suggestion,
+    /// The time of the selection
+    pub timestamp: SystemTime,
+}
+
+/// Keeps the selections of `AutosuggestSession`s, e.g. to analyze the quality of the suggestions.
+///
+/// A selection is recorded while the user waits for it, and a failing recorder must not fail
+/// the selection, so `record` does not return errors: the recorder keeps them itself, like
+/// `JsonlSelectionRecorder::take_error`.
+///
+/// Any `Fn(&Selection)` closure is a `SelectionRecorder`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let w3_client = W3WClient::new("your_api_key");
+/// let recorder = JsonlSelectionRecorder::create("selections.jsonl")?;
+/// let mut session = AutosuggestSession::new(&w3_client, AutoSuggestOptions::default())
+///     .recorder(&recorder);
+/// session.update("filled.count.so")?;
+/// let selected = session.flush()?[0].clone();
+/// session.select(&selected)?;
+/// if let Some(error) = recorder.take_error() {
+///     eprintln!("the selection was not recorded: {}", error);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub trait SelectionRecorder {
+    /// Record the selection.
+    fn record(&self, selection: &Selection);
+}
+
+impl<F> SelectionRecorder for F
+where
+    F: Fn(&Selection),
+{
+    fn record(&self, selection: &Selection) {
+        self(selection)
+    }
+}
+
+/// A `SelectionRecorder` which writes every selection as a line of JSON, e.g. to a file.
+///
+/// A line holds the `timestamp` in milliseconds since the Unix epoch, the `input`, the
+/// `suggestions` shown with their `words`, `rank`, `country` and `nearestPlace`, and the
+/// `selection` and its `rank`, like `autosuggest_selection` reports them:
+///
+/// ```text
+/// {"input":"filled.count.so","rank":1,"selection":"filled.count.soap","suggestions":[...],"timestamp":1760601600000}
+/// ```
+#[derive(Debug)]
+pub struct JsonlSelectionRecorder<W> {
+    writer: Mutex<W>,
+    error: Mutex<Option<io::Error>>,
+}
+
+impl JsonlSelectionRecorder<File> {
+    /// Append the selections to the file at `path`, which is created if it does not exist.
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(JsonlSelectionRecorder::new(file))
+    }
+}
+
+impl<W: Write> JsonlSelectionRecorder<W> {
+    /// Write the selections to `writer`.
+    pub fn new(writer: W) -> Self {
+        JsonlSelectionRecorder {
+            writer: Mutex::new(writer),
+            error: Mutex::new(None),
+        }
+    }
+
+    /// Return the last error of writing a selection, if any, and clear it.
+    pub fn take_error(&self) -> Option<io::Error> {
+        self.error
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take()
+    }
+
+    /// Return the writer, e.g. to read back the selections written to a buffer.
+    pub fn into_inner(self) -> W {
+        self.writer
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<W: Write> SelectionRecorder for JsonlSelectionRecorder<W> {
+    fn record(&self, selection: &Selection) {
+        let timestamp = selection
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let suggestions: Vec<_> = selection
+            .suggestions
+            .iter()
+            .map(|suggestion| {
+                json!({
+                    "words": suggestion.words,
+                    "rank": suggestion.rank,
+                    "country": suggestion.country.as_str(),
+                    "nearestPlace": suggestion.nearest_place,
+                })
+            })
+            .collect();
+        let mut line = json!({
+            "timestamp": timestamp,
+            "input": selection.input,
+            "suggestions": suggestions,
+            "selection": selection.selected.words,
+            "rank": selection.selected.rank,
+        })
+        .to_string();
+        line.push('\n');
+        // One write per line, so lines of concurrent sessions do not interleave.
+        let mut writer = self
+            .writer
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Err(error) = writer
+            .write_all(line.as_bytes())
+            .and_then(|()| writer.flush())
+        {
+            *self
+                .error
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Duration;
+
+    use super::{AutosuggestSession, JsonlSelectionRecorder};
+    use crate::{AutoSuggestOptions, AutosuggestCache, Suggestion, W3WClient, W3WError};
+
+    #[test]
+    fn test_updates_within_the_debounce_time_are_held_back() {
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let mut session = AutosuggestSession::new(&w3_client, AutoSuggestOptions::default())
+            .debounce(Duration::from_secs(3600));
+
+        assert!(matches!(
+            session.update("filled.count"),
+            Err(W3WError::Validation(_))
+        ));
+        assert!(matches!(session.update("filled.count.so"), Ok(None)));
+        assert!(matches!(session.flush(), Err(W3WError::Validation(_))));
+    }
+
+    #[test]
+    fn test_select_reports_the_rank_of_the_api() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 4096];
+            let read = stream.read(&mut request).unwrap();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{{}}"
+            )
+            .unwrap();
+            String::from_utf8_lossy(&request[..read]).into_owned()
+        });
+
+        let suggestion = |words: &str, country: &str, rank: u32| Suggestion {
+            country: country.parse().unwrap(),
+            nearest_place: String::from("Somewhere"),
+            words: words.to_string(),
+            rank,
+            language: String::from("en"),
+            distance_to_focus_km: None,
+            square: None,
+            coordinates: None,
+            map: None,
+        };
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = format!("http://127.0.0.1:{}", port);
+        let cache = AutosuggestCache::new(10);
+        cache.insert(
+            String::from("input=filled.count.so"),
+            vec![
+                suggestion("filled.count.soap", "GB", 1),
+                suggestion("filled.count.soaps", "BE", 2),
+            ],
+        );
+        w3_client.autosuggest_cache = Some(cache);
+        w3_client.suggestion_ranker = Some(Box::new(|suggestion: &Suggestion| {
+            if suggestion.country.as_str() == "BE" {
+                1.0
+            } else {
+                0.0
+            }
+        }));
+
+        let mut session = AutosuggestSession::new(&w3_client, AutoSuggestOptions::default());
+        let suggestions = session.update("filled.count.so").unwrap().unwrap();
+        let selected = suggestions[1].clone();
+        assert_eq!(selected.words, "filled.count.soap");
+        session.select(&selected).unwrap();
+        let request = server.join().unwrap();
+        assert!(
+            request.contains("selection=filled.count.soap&rank=1&"),
+            "{}",
+            request
+        );
+    }
+
+    #[test]
+    fn test_select_is_recorded_also_when_the_report_fails() {
+        let suggestion = |words: &str, rank: u32| Suggestion {
+            country: "GB".parse().unwrap(),
+            nearest_place: String::from("Bayswater, London"),
+            words: words.to_string(),
+            rank,
+            language: String::from("en"),
+            distance_to_focus_km: None,
+            square: None,
+            coordinates: None,
+            map: None,
+        };
+        let mut w3_client = W3WClient::new("mock-api-key");
+        w3_client.host = String::from("invalid host");
+        let cache = AutosuggestCache::new(10);
+        cache.insert(
+            String::from("input=filled.count.so"),
+            vec![
+                suggestion("filled.count.soap", 1),
+                suggestion("filled.count.soaps", 2),
+            ],
+        );
+        w3_client.autosuggest_cache = Some(cache);
+        let recorder = JsonlSelectionRecorder::new(Vec::new());
+
+        let mut session =
+            AutosuggestSession::new(&w3_client, AutoSuggestOptions::default()).recorder(&recorder);
+        let selected = session.update("filled.count.so").unwrap().unwrap()[1].clone();
+        assert!(matches!(
+            session.select(&selected),
+            Err(W3WError::Validation(_))
+        ));
+        assert!(recorder.take_error().is_none());
+
+        let output = String::from_utf8(recorder.into_inner()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let line: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(line["input"], "filled.count.so");
+        assert_eq!(line["selection"], "filled.count.soaps");
+        assert_eq!(line["rank"], 2);
+        assert_eq!(line["suggestions"][0]["words"], "filled.count.soap");
+        assert_eq!(line["suggestions"][1]["nearestPlace"], "Bayswater, London");
+        assert!(line["timestamp"].as_u64().unwrap() > 0);
+    }
+}
@@ -0,0 +1,103 @@
+//! A `Square` is one of the 3 meter by 3 meter squares of the what3words grid, as returned in the
+//! `square` field of the conversion endpoints. Squares are not exactly 3 meters wide: their
+//! real size on the ground depends on the latitude, see `Square::approx_size_m`.
+
+use serde::Deserialize;
+
+use crate::bounding_box::BoundingBox;
+use crate::coordinate::Coordinate;
+use crate::polygon::Polygon;
+
+/// The four squares which share an edge with a square, or a value for each of them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Neighbors<T> {
+    /// The square to the north
+    pub north: T,
+    /// The square to the east
+    pub east: T,
+    /// The square to the south
+    pub south: T,
+    /// The square to the west
+    pub west: T,
+}
+
+/// A what3words square defined by the coordinate of its southwestern corner and the coordinate
+/// of its northeastern corner.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct Square {
+    /// Coordinates of the southwestern corner
+    #[serde(rename = "southwest")]
+    pub south_west: Coordinate,
+    /// Coordinates of the northeastern corner
+    #[serde(rename = "northeast")]
+    pub north_east: Coordinate,
+}
+
+impl Square {
+    /// Return the coordinate in the middle of the square.
+    pub fn center(&self) -> Coordinate {
+        Coordinate {
+            latitude: (self.south_west.latitude + self.north_east.latitude) / 2.0,
+            longitude: (self.south_west.longitude + self.north_east.longitude) / 2.0,
+        }
+    }
+
+    /// Return the centers of the four adjacent squares. The grid is regular at this scale, so
+    /// they are one square height or width away from the center of this square.
+    pub fn neighbor_centers(&self) -> Neighbors<Coordinate> {
+        let center = self.center();
+        let d_lat = self.north_east.latitude - self.south_west.latitude;
+        let d_lng = self.north_east.longitude - self.south_west.longitude;
+        let offset = |lat_steps: f64, lng_steps: f64| Coordinate {
+            latitude: center.latitude + lat_steps * d_lat,
+            longitude: center.longitude + lng_steps * d_lng,
+        };
+        Neighbors {
+            north: offset(1.0, 0.0),
+            east: offset(0.0, 1.0),
+            south: offset(-1.0, 0.0),
+            west: offset(0.0, -1.0),
+        }
+    }
+
+    /// Return whether the coordinate lies within the square. The southern and western edges
+    /// belong to the square and the northern and eastern edges to its neighbors, so a
+    /// coordinate on an edge is within exactly one square.
+    pub fn contains(&self, coordinate: &Coordinate) -> bool {
+        (self.south_west.latitude..self.north_east.latitude).contains(&coordinate.latitude)
+            && (self.south_west.longitude..self.north_east.longitude)
+                .contains(&coordinate.longitude)
+    }
+
+    /// Return the square as a `BoundingBox`, e.g. to fetch the grid section around it.
+    pub fn to_bounding_box(&self) -> BoundingBox {
+        BoundingBox {
+            south_west: self.south_west,
+            north_east: self.north_east,
+        }
+    }
+
+    /// Return the four corners of the square as a `Polygon`, counterclockwise from the
+    /// southwestern corner.
+    pub fn to_polygon(&self) -> Polygon {
+        self.to_bounding_box().to_polygon()
+    }
+
+    /// Return the square as a Well-Known Text polygon of its four corners, e.g. to load the
+    /// squares of addresses into a spatial database.
+    pub fn to_wkt(&self) -> String {
+        self.to_polygon().to_wkt()
+    }
+
+    /// Return the approximate width (east-west) and height (north-south) of the square on the
+    /// ground in meters. The width is measured at the latitude of the center of the square.
+    pub fn approx_size_m(&self) -> (f64, f64) {
+        self.to_bounding_box().approx_size_m()
+    }
+
+    /// Return the approximate area of the square on the ground in square meters.
+    pub fn approx_area_m2(&self) -> f64 {
+        let (width, height) = self.approx_size_m();
+        width * height
+    }
+}
@@ -0,0 +1,223 @@
+//! The `Suggestion` is a single result of the autosuggest endpoints. The location of the square
+//! is only part of the `autosuggest-with-coordinates` response. `SuggestionsExt` sorts and
+//! filters a list of suggestions, `merge_suggestions` combines the lists of several calls and a
+//! `SuggestionRanker` re-orders the suggestions of the typed autosuggest calls.
+
+use std::cmp::Ordering;
+
+use serde::Deserialize;
+
+use crate::coordinate::Coordinate;
+use crate::country_code::CountryCode;
+use crate::square::Square;
+
+/// A three word address suggested for an incomplete or misspelled input.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Suggestion {
+    /// The country the square is in, `ZZ` if it is not within a country
+    pub country: CountryCode,
+    /// The nearest place to the square
+    pub nearest_place: String,
+    /// The suggested three word address
+    pub words: String,
+    /// The rank the API gave the suggestion, starting at 1 for the best match. It is kept when
+    /// the suggestions are re-ordered, so `autosuggest_selection` reports the rank the API
+    /// returned.
+    pub rank: u32,
+    /// The language of the three word address
+    pub language: String,
+    /// The distance to the focus in kilometres, if a focus was given
+    #[serde(default)]
+    pub distance_to_focus_km: Option<f64>,
+    /// The square which is addressed, only returned by `autosuggest-with-coordinates`
+    #[serde(default)]
+    pub square: Option<Square>,
+    /// The coordinates of the center of the square, only returned by
+    /// `autosuggest-with-coordinates`
+    #[serde(default)]
+    pub coordinates: Option<Coordinate>,
+    /// A link to the square on the what3words map, only returned by
+    /// `autosuggest-with-coordinates`
+    #[serde(default)]
+    pub map: Option<String>,
+}
+
+/// The body of an autosuggest response.
+#[derive(Deserialize)]
+pub(crate) struct Suggestions {
+    pub(crate) suggestions: Vec<Suggestion>,
+}
+
+/// Sorting and filtering of the suggestions returned by the typed autosuggest calls. Every
+/// method takes and returns the list, so they can be chained.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::*;
+/// # fn main() -> Result<(), what3words::W3WError> {
+/// # let w3_client = W3WClient::new("your_api_key");
+/// # let options = AutoSuggestOptions::default();
+/// let suggestions = w3_client
+///     .autosuggest_typed("filled.count.so", &options)?
+///     .without_sea()
+///     .filter_country("GB")
+///     .sort_by_distance()
+///     .top(3);
+/// # Ok(())
+/// # }
+/// ```
+pub trait SuggestionsExt {
+    /// Sort by distance to the focus, nearest first. Suggestions without a distance are put
+    /// last, in their original order.
+    fn sort_by_distance(self) -> Self;
+
+    /// Keep the suggestions in the given country, compared case-insensitively.
+    fn filter_country(self, country: &str) -> Self;
+
+    /// Drop the suggestions which are not within a country, i.e. at sea, with country `ZZ`.
+    fn without_sea(self) -> Self;
+
+    /// Keep the first `n` suggestions.
+    fn top(self, n: usize) -> Self;
+}
+
+impl SuggestionsExt for Vec<Suggestion> {
+    fn sort_by_distance(mut self) -> Self {
+        self.sort_by(
+            |a, b| match (a.distance_to_focus_km, b.distance_to_focus_km) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        );
+        self
+    }
+
+    fn filter_country(mut self, country: &str) -> Self {
+        self.retain(|suggestion| suggestion.country.as_str().eq_ignore_ascii_case(country));
+        self
+    }
+
+    fn without_sea(mut self) -> Self {
+        self.retain(|suggestion| suggestion.country.as_str() != "ZZ");
+        self
+    }
+
+    fn top(mut self, n: usize) -> Self {
+        self.truncate(n);
+        self
+    }
+}
+
+/// Merge the suggestions of several autosuggest calls, e.g. for variants of the same input,
+/// into a single list without duplicate words. A suggestion which occurs more than once keeps
+/// its best rank and smallest distance to the focus. The list is ordered by that rank, then by
+/// the number of lists the suggestion was in and then by distance. The `rank` of every
+/// suggestion stays a rank the API returned, so several suggestions may share a rank.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::*;
+/// # fn main() -> Result<(), what3words::W3WError> {
+/// # let w3_client = W3WClient::new("your_api_key");
+/// # let options = AutoSuggestOptions::default();
+/// let typed = w3_client.autosuggest_typed("filled.count.soa", &options)?;
+/// let corrected = w3_client.autosuggest_typed("filled.count.sop", &options)?;
+/// let suggestions = merge_suggestions([typed, corrected]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn merge_suggestions(lists: impl IntoIterator<Item = Vec<Suggestion>>) -> Vec<Suggestion> {
+    let mut merged: Vec<(Suggestion, usize)> = Vec::new();
+    for suggestion in lists.into_iter().flatten() {
+        match merged
+            .iter_mut()
+            .find(|(existing, _)| existing.words == suggestion.words)
+        {
+            Some((existing, occurrences)) => {
+                *occurrences += 1;
+                existing.rank = existing.rank.min(suggestion.rank);
+                existing.distance_to_focus_km = match (
+                    existing.distance_to_focus_km,
+                    suggestion.distance_to_focus_km,
+                ) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                };
+                if existing.coordinates.is_none() {
+                    existing.square = suggestion.square;
+                    existing.coordinates = suggestion.coordinates;
+                    existing.map = suggestion.map;
+                }
+            }
+            None => merged.push((suggestion, 1)),
+        }
+    }
+    merged.sort_by(|(a, a_occurrences), (b, b_occurrences)| {
+        a.rank
+            .cmp(&b.rank)
+            .then(b_occurrences.cmp(a_occurrences))
+            .then_with(|| {
+                let distance = |s: &Suggestion| s.distance_to_focus_km.unwrap_or(f64::INFINITY);
+                distance(a).total_cmp(&distance(b))
+            })
+    });
+    merged
+        .into_iter()
+        .map(|(suggestion, _)| suggestion)
+        .collect()
+}
+
+/// Scores suggestions with signals of your own, like the addresses a user picked before or the
+/// zones you deliver to. When set as the `suggestion_ranker` of the client, the typed
+/// autosuggest calls order their suggestions by descending score. Suggestions with the same
+/// score keep the order of the API, and every suggestion keeps the `rank` of the API.
+///
+/// Any `Fn(&Suggestion) -> f64` closure which is `Send` and `Sync` is a `SuggestionRanker`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::*;
+/// # fn main() -> Result<(), what3words::W3WError> {
+/// let mut w3_client = W3WClient::new("<your API key>");
+/// w3_client.suggestion_ranker = Some(Box::new(|suggestion: &Suggestion| {
+///     if suggestion.country.as_str() == "GB" { 1.0 } else { 0.0 }
+/// }));
+/// # Ok(())
+/// # }
+/// ```
+pub trait SuggestionRanker: Send + Sync {
+    /// Return the score of the suggestion, the higher the better.
+    fn score(&self, suggestion: &Suggestion) -> f64;
+}
+
+impl<F> SuggestionRanker for F
+where
+    F: Fn(&Suggestion) -> f64 + Send + Sync,
+{
+    fn score(&self, suggestion: &Suggestion) -> f64 {
+        self(suggestion)
+    }
+}
+
+/// Order the suggestions by the score of `ranker`, best first. Their `rank` is left as the API
+/// returned it.
+pub(crate) fn rerank(
+    ranker: &dyn SuggestionRanker,
+    suggestions: Vec<Suggestion>,
+) -> Vec<Suggestion> {
+    let mut scored: Vec<(f64, Suggestion)> = suggestions
+        .into_iter()
+        .map(|suggestion| (ranker.score(&suggestion), suggestion))
+        .collect();
+    scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    scored
+        .into_iter()
+        .map(|(_, suggestion)| suggestion)
+        .collect()
+}
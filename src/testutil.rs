@@ -0,0 +1,166 @@
+//! Generators of random coordinates and shapes for tests, behind the `testutil` feature:
+//! coordinates within a box, bounding boxes which fit a grid section and polygons which fit a
+//! `clip-to-polygon`.
+//!
+//! A `RandomShapes` created with a seed generates the same shapes on every run, so a failing
+//! test can be repeated.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use what3words::testutil::RandomShapes;
+//! # use what3words::*;
+//! # fn main() -> Result<(), what3words::W3WError> {
+//! # let w3_client = W3WClient::new("your_api_key");
+//! # let options = GridSectionOptions::default();
+//! let mut shapes = RandomShapes::new(42);
+//! for _ in 0..100 {
+//!     let bounding_box = shapes.bounding_box();
+//!     assert!(w3_client.grid_section_typed(&bounding_box, &options).is_ok());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::bounding_box::BoundingBox;
+use crate::coordinate::{random_u64, Coordinate};
+use crate::polygon::{Polygon, MAX_POLYGON_PAIRS};
+
+/// The largest side, in meters, of a box of `RandomShapes::bounding_box`, which keeps its
+/// diagonal below the 4 km of a grid section.
+const MAX_BOX_SIDE_M: f64 = 2500.0;
+/// The largest radius, in meters, of a polygon of `RandomShapes::polygon`.
+const MAX_POLYGON_RADIUS_M: f64 = 5000.0;
+/// The latitudes of the random centers of shapes, away from the poles where the longitudes of
+/// a small shape spread out.
+const MAX_SHAPE_LATITUDE: f64 = 80.0;
+
+/// A generator of random coordinates and shapes, with a small SplitMix64 generator of its own.
+#[derive(Debug, Clone)]
+pub struct RandomShapes {
+    state: u64,
+}
+
+impl RandomShapes {
+    /// Creates a generator which generates the same shapes for the same seed.
+    pub fn new(seed: u64) -> Self {
+        RandomShapes { state: seed }
+    }
+
+    /// Creates a generator with a random seed.
+    pub fn from_entropy() -> Self {
+        RandomShapes::new(random_u64())
+    }
+
+    /// Return a random number within 0 (included) and 1 (excluded).
+    pub fn next_f64(&mut self) -> f64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Return a random valid coordinate anywhere on earth.
+    pub fn coordinate(&mut self) -> Coordinate {
+        Coordinate {
+            latitude: self.between(-90.0, 90.0),
+            longitude: self.between(-180.0, 180.0),
+        }
+    }
+
+    /// Return a random coordinate within `bounding_box`.
+    pub fn coordinate_within(&mut self, bounding_box: &BoundingBox) -> Coordinate {
+        Coordinate {
+            latitude: self.between(
+                bounding_box.south_west.latitude,
+                bounding_box.north_east.latitude,
+            ),
+            longitude: self.between(
+                bounding_box.south_west.longitude,
+                bounding_box.north_east.longitude,
+            ),
+        }
+    }
+
+    /// Return a random bounding box of at most 2.5 by 2.5 km, which fits a grid section.
+    pub fn bounding_box(&mut self) -> BoundingBox {
+        let center = self.shape_center();
+        let width_m = self.between(1.0, MAX_BOX_SIDE_M);
+        let height_m = self.between(1.0, MAX_BOX_SIDE_M);
+        BoundingBox::from_center(center, width_m, height_m)
+    }
+
+    /// Return a random polygon of 3 to 24 coordinates, so the closed ring fits the 25 pairs of
+    /// a `clip-to-polygon`, within 5 km of a random center. The polygon does not cross itself.
+    pub fn polygon(&mut self) -> Polygon {
+        let center = self.shape_center();
+        self.polygon_around(center, MAX_POLYGON_RADIUS_M)
+    }
+
+    /// Return a random polygon of 3 to 24 coordinates within `radius_m` meters of `center`.
+    /// The coordinates run counter-clockwise around the center, so the polygon does not cross
+    /// itself.
+    pub fn polygon_around(&mut self, center: Coordinate, radius_m: f64) -> Polygon {
+        let count = 3 + (self.next_f64() * (MAX_POLYGON_PAIRS - 3) as f64) as usize;
+        let mut angles: Vec<f64> = (0..count).map(|_| self.between(0.0, 360.0)).collect();
+        angles.sort_by(|a, b| b.total_cmp(a));
+        Polygon {
+            coordinates: angles
+                .into_iter()
+                .map(|bearing| {
+                    let distance_m = self.between(radius_m / 10.0, radius_m);
+                    center.destination(bearing, distance_m)
+                })
+                .collect(),
+        }
+    }
+
+    /// Return a random number within `low` and `high`.
+    fn between(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.next_f64()
+    }
+
+    /// Return a random center for a shape, away from the poles and the antimeridian.
+    fn shape_center(&mut self) -> Coordinate {
+        Coordinate {
+            latitude: self.between(-MAX_SHAPE_LATITUDE, MAX_SHAPE_LATITUDE),
+            longitude: self.between(-179.0, 179.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RandomShapes;
+    use crate::polygon::Polygon;
+
+    #[test]
+    fn test_random_shapes_are_valid_and_repeatable() {
+        let mut shapes = RandomShapes::new(7);
+        for _ in 0..200 {
+            let bounding_box = shapes.bounding_box();
+            assert!(bounding_box.validate().is_ok());
+            assert!(bounding_box.validate_grid_section().is_ok());
+            let coordinate = shapes.coordinate_within(&bounding_box);
+            assert!(bounding_box.contains(&coordinate));
+            assert!(shapes.coordinate().validate().is_ok());
+
+            let polygon = shapes.polygon();
+            assert!(Polygon::new(polygon.coordinates.clone()).is_ok());
+            assert_eq!(
+                polygon.normalized().coordinates.len(),
+                polygon.coordinates.len()
+            );
+        }
+        assert_eq!(
+            RandomShapes::new(7).polygon(),
+            RandomShapes::new(7).polygon()
+        );
+        assert_ne!(
+            RandomShapes::new(7).coordinate(),
+            RandomShapes::new(8).coordinate()
+        );
+    }
+}
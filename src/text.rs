@@ -0,0 +1,359 @@
+//! Offline helpers to recognize three word addresses in user input, without calling the
+//! what3words API. These mirror the regular expressions of the official what3words wrappers.
+//!
+//! - `is_possible_3wa` checks whether a text has the shape of a three word address;
+//! - `find_possible_3wa` finds all candidate addresses in a free text;
+//! - `did_you_mean` recognizes input which is almost a three word address, e.g. with the wrong
+//!   separators, and is worth sending to autosuggest;
+//! - `fix_separators` turns such input into a three word address with dots;
+//! - `clean_transcript` prepares speech-to-text output for the `generic-voice` input type;
+//! - `ocr_candidates` repairs three word addresses read by OCR from photographed labels.
+
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+/// The separators used between the words in the different what3words languages, like the `・`
+/// in `こくさい・ていか・かざす`.
+const SEPARATORS: [char; 12] = [
+    '.', '｡', '。', '･', '・', '︒', '។', '։', '။', '۔', '።', '।',
+];
+/// The separators which are mistakenly used between words, next to the `SEPARATORS`.
+const LOOSE_SEPARATORS: [char; 14] = [
+    ':', '^', '_', ' ', ',', '\\', '/', '+', '\'', '&', ';', '|', '\u{3000}', '-',
+];
+/// The characters which are never part of a word, next to digits, whitespace and control
+/// characters.
+const NON_WORD_CHARACTERS: [char; 37] = [
+    '`', '~', '!', '@', '#', '$', '%', '^', '&', '*', '(', ')', '+', '-', '_', '=', '[', '{', ']',
+    '}', '\\', '|', '\'', '"', '<', '>', '.', ',', '?', '/', ';', ':', '£', '§', 'º', '©', '®',
+];
+
+/// Words which speech recognition picks up around an address, e.g. the spoken separators of
+/// `///filled.count.soap` or a polite `please`.
+const FILLER_WORDS: [&str; 9] = [
+    "uh", "um", "er", "erm", "hmm", "please", "dot", "slash", "slashes",
+];
+
+/// The largest number of candidates `ocr_candidates` returns.
+const MAX_OCR_CANDIDATES: usize = 8;
+
+/// Return whether the character separates the words of a three word address.
+pub(crate) fn is_separator(c: char) -> bool {
+    SEPARATORS.contains(&c)
+}
+
+/// Return whether the character may be part of a word of a three word address: anything but
+/// digits, punctuation, whitespace and control characters.
+pub(crate) fn is_word_character(c: char) -> bool {
+    !c.is_ascii_digit()
+        && !c.is_whitespace()
+        && !c.is_control()
+        && !NON_WORD_CHARACTERS.contains(&c)
+}
+
+/// Return a regex character class of the given characters, escaped, between the raw regex
+/// `prefix` and `suffix`, e.g. a `^` prefix to negate the class.
+fn character_class<'a>(
+    prefix: &str,
+    chars: impl IntoIterator<Item = &'a char>,
+    suffix: &str,
+) -> String {
+    let chars: String = chars
+        .into_iter()
+        .map(|c| regex::escape(c.encode_utf8(&mut [0; 4])))
+        .collect();
+    format!("[{}{}{}]", prefix, chars, suffix)
+}
+
+/// A single word, matching the characters of `is_word_character`.
+fn word() -> String {
+    format!(
+        "{}+",
+        character_class("^0-9", &NON_WORD_CHARACTERS, r"\s\p{Cc}")
+    )
+}
+
+/// One of the `SEPARATORS`.
+fn separator() -> String {
+    character_class("", &SEPARATORS, "")
+}
+
+/// One of the `SEPARATORS` or `LOOSE_SEPARATORS`.
+fn loose_separator() -> String {
+    character_class("", SEPARATORS.iter().chain(&LOOSE_SEPARATORS), "")
+}
+
+fn possible_3wa_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(&format!(
+            r"^/*{w}{s}{w}{s}{w}$",
+            w = word(),
+            s = separator()
+        ))
+        .unwrap()
+    })
+}
+
+fn find_3wa_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(&format!(r"{w}{s}{w}{s}{w}", w = word(), s = separator())).unwrap()
+    })
+}
+
+fn did_you_mean_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(&format!(
+            r"^/*{w}{s}{{1,2}}{w}{s}{{1,2}}{w}$",
+            w = word(),
+            s = loose_separator()
+        ))
+        .unwrap()
+    })
+}
+
+fn loose_separator_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(&format!("{}+", loose_separator())).unwrap())
+}
+
+/// Return whether the text has the shape of a three word address, e.g. `filled.count.soap` or
+/// `///filled.count.soap`. This does not guarantee that the address exists.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::text::*;
+/// assert!(is_possible_3wa("filled.count.soap"));
+/// assert!(!is_possible_3wa("filled count soap"));
+/// ```
+pub fn is_possible_3wa(text: &str) -> bool {
+    possible_3wa_regex().is_match(text)
+}
+
+/// Return all possible three word addresses in a free text, in order of appearance.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::text::*;
+/// let found = find_possible_3wa("Deliver to filled.count.soap or index.home.raft please");
+/// assert_eq!(found, vec!["filled.count.soap", "index.home.raft"]);
+/// ```
+pub fn find_possible_3wa(text: &str) -> Vec<&str> {
+    find_3wa_regex()
+        .find_iter(text)
+        .map(|found| found.as_str())
+        .collect()
+}
+
+/// Return whether the text looks like a three word address with the wrong separators, e.g.
+/// `filled count soap` or `filled-count-soap`. Such input is worth sending to autosuggest.
+/// Valid three word addresses match as well.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::text::*;
+/// assert!(did_you_mean("filled count soap"));
+/// assert!(!did_you_mean("filled count"));
+/// ```
+pub fn did_you_mean(text: &str) -> bool {
+    did_you_mean_regex().is_match(text)
+}
+
+/// Return the input with its separators replaced by dots, when it is almost a three word
+/// address according to `did_you_mean`. Returns `None` for any other input.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::text::*;
+/// assert_eq!(fix_separators("filled, count, soap").as_deref(), Some("filled.count.soap"));
+/// assert_eq!(fix_separators("filled count"), None);
+/// ```
+pub fn fix_separators(text: &str) -> Option<String> {
+    if !did_you_mean(text) {
+        return None;
+    }
+    let words: Vec<&str> = loose_separator_regex()
+        .split(text.trim_start_matches('/'))
+        .collect();
+    Some(words.join("."))
+}
+
+/// Clean a speech-to-text transcript into the lowercase, space-separated words which the
+/// `generic-voice` input type of autosuggest expects. Punctuation and digits are dropped, as
+/// are filler words like `um`, `please` and the spoken separators `dot` and `slash`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::text::*;
+/// assert_eq!(clean_transcript("Filled, count, soap please."), "filled count soap");
+/// assert_eq!(clean_transcript("um slash slash slash filled dot count dot soap"), "filled count soap");
+/// ```
+pub fn clean_transcript(transcript: &str) -> String {
+    let transcript: String = transcript
+        .chars()
+        .map(|c| if c.is_alphabetic() { c } else { ' ' })
+        .collect::<String>()
+        .to_lowercase();
+    transcript
+        .split_whitespace()
+        .filter(|word| !FILLER_WORDS.contains(word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Repair a three word address read by OCR, best-effort. Digits which OCR confuses with
+/// letters are replaced (`0` by `o`, `1` by `l` or `i`, `5` by `s`, `8` by `b`) and the
+/// separators are fixed as in `fix_separators`. Returns at most 8 candidates in order of
+/// likelihood, to feed into autosuggest; an empty list means no repair looked like an address.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::text::*;
+/// assert_eq!(ocr_candidates("fi11ed count s0ap")[0], "filled.count.soap");
+/// ```
+pub fn ocr_candidates(text: &str) -> Vec<String> {
+    let mut variants = vec![String::new()];
+    for c in text.trim().to_lowercase().chars() {
+        let replacements: &[char] = match c {
+            '0' => &['o'],
+            '1' | '|' => &['l', 'i'],
+            '5' => &['s'],
+            '8' => &['b'],
+            _ => &[],
+        };
+        if replacements.is_empty() {
+            variants.iter_mut().for_each(|variant| variant.push(c));
+            continue;
+        }
+        variants = replacements
+            .iter()
+            .flat_map(|&replacement| {
+                variants.iter().map(move |variant| {
+                    let mut variant = variant.clone();
+                    variant.push(replacement);
+                    variant
+                })
+            })
+            .take(MAX_OCR_CANDIDATES)
+            .collect();
+    }
+    let mut candidates: Vec<String> = Vec::new();
+    for candidate in variants
+        .iter()
+        .filter_map(|variant| fix_separators(variant))
+    {
+        if !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        clean_transcript, did_you_mean, find_possible_3wa, fix_separators, is_possible_3wa,
+        ocr_candidates,
+    };
+    use crate::ThreeWordAddress;
+
+    #[test]
+    fn test_address_parsing_agrees_with_is_possible_3wa() {
+        for input in [
+            "filled.count.soap",
+            "///filled.count.soap",
+            "こくさい・ていか・かざす",
+            "ขนม។ปลา។ไก่",
+            "filled.count.s0ap",
+            "filled.count.£oap",
+            "filled.count.©oap",
+            "filled.co\"unt.soap",
+            "filled.count.so\u{7}p",
+            "filled.count",
+            "filled.count.soap.index",
+            "filled-count.soap",
+        ] {
+            assert_eq!(
+                is_possible_3wa(input),
+                input.parse::<ThreeWordAddress>().is_ok(),
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_possible_3wa() {
+        assert!(is_possible_3wa("filled.count.soap"));
+        assert!(is_possible_3wa("///filled.count.soap"));
+        assert!(is_possible_3wa("こくさい・ていか・かざす"));
+        assert!(!is_possible_3wa("filled.count"));
+        assert!(!is_possible_3wa("filled count soap"));
+        assert!(!is_possible_3wa("filled.count.soap1"));
+    }
+
+    #[test]
+    fn test_find_possible_3wa() {
+        assert_eq!(
+            find_possible_3wa("Deliver to filled.count.soap or ///index.home.raft, thanks."),
+            vec!["filled.count.soap", "index.home.raft"]
+        );
+        assert!(find_possible_3wa("No address in here.").is_empty());
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        assert!(did_you_mean("filled count soap"));
+        assert!(did_you_mean("filled-count-soap"));
+        assert!(did_you_mean("filled, count, soap"));
+        assert!(did_you_mean("filled.count.soap"));
+        assert!(!did_you_mean("filled count"));
+        assert!(!did_you_mean("filled   count   soap"));
+    }
+
+    #[test]
+    fn test_fix_separators() {
+        assert_eq!(
+            fix_separators("filled, count, soap").as_deref(),
+            Some("filled.count.soap")
+        );
+        assert_eq!(
+            fix_separators("///filled-count-soap").as_deref(),
+            Some("filled.count.soap")
+        );
+        assert_eq!(fix_separators("filled count"), None);
+    }
+
+    #[test]
+    fn test_clean_transcript() {
+        assert_eq!(
+            clean_transcript("Filled, count, soap please."),
+            "filled count soap"
+        );
+        assert_eq!(
+            clean_transcript("um slash slash slash filled dot count dot soap"),
+            "filled count soap"
+        );
+        assert_eq!(clean_transcript("  "), "");
+    }
+
+    #[test]
+    fn test_ocr_candidates() {
+        let candidates = ocr_candidates("FI11ED count s0ap");
+        assert_eq!(candidates[0], "filled.count.soap");
+        assert!(candidates.contains(&String::from("fiiied.count.soap")));
+        assert!(candidates.len() <= 8);
+
+        assert_eq!(ocr_candidates("index home raft"), ["index.home.raft"]);
+        assert!(ocr_candidates("1234").is_empty());
+    }
+}
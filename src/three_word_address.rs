@@ -0,0 +1,161 @@
+//! A `ThreeWordAddress` is a validated what3words address in the form `word.word.word`.
+//! Validating an address locally catches malformed input before an API call is spent on it.
+//!
+//! Addresses are often pasted in other shapes, like `///filled.count.soap`,
+//! `https://w3w.co/filled.count.soap` or `filled count soap`. `ThreeWordAddress::normalize`
+//! turns all of these into the canonical `filled.count.soap`.
+//!
+//! Addresses in other scripts use their own separators, like the `・` in
+//! `こくさい・ていか・かざす`. These are recognized as well and replaced with the dot, which the
+//! what3words API accepts for every language.
+
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use crate::error::W3WError;
+use crate::text;
+
+/// Hosts of which the path of a URL is treated as a three word address.
+const W3W_URL_HOSTS: [&str; 4] = [
+    "w3w.co",
+    "www.w3w.co",
+    "what3words.com",
+    "www.what3words.com",
+];
+
+/// Return whether the character separates the words of an address: one of the separators of
+/// the what3words languages or whitespace, including the ideographic space.
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || text::is_separator(c)
+}
+
+/// A three word address, e.g. `filled.count.soap`. The words are stored in lowercase.
+/// Input is normalized with `ThreeWordAddress::normalize` before it is validated.
+///
+/// # Example
+///
+/// ```no_run
+/// # use what3words::*;
+/// # fn main() -> Result<(), what3words::W3WError> {
+/// # let w3_client = W3WClient::new("your_api_key");
+/// let address: ThreeWordAddress = "Filled.Count.Soap".parse()?;
+/// assert_eq!(address.to_string(), "filled.count.soap");
+/// let resp = w3_client.convert_to_coordinates(address, &ConvertToCoordinatesOptions::default());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ThreeWordAddress(String);
+
+impl ThreeWordAddress {
+    /// Extract the three word address from user input. The `///` prefix and the scheme and host
+    /// of `w3w.co` and `what3words.com` URLs are stripped, and every run of whitespace, dots and
+    /// the separators of other scripts between the words is replaced with a single dot. Partial
+    /// input is kept partial, so this is also usable for autosuggest input.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use what3words::*;
+    /// assert_eq!(ThreeWordAddress::normalize("https://w3w.co/filled.count.soap"), "filled.count.soap");
+    /// assert_eq!(ThreeWordAddress::normalize("///filled.count.soap"), "filled.count.soap");
+    /// assert_eq!(ThreeWordAddress::normalize(" filled count so"), "filled.count.so");
+    /// ```
+    pub fn normalize(input: &str) -> String {
+        let mut input = input.trim();
+        if let Some(index) = input.find("://") {
+            input = &input[index + 3..];
+        }
+        if let Some((host, path)) = input.split_once('/') {
+            if W3W_URL_HOSTS.contains(&host.to_lowercase().as_str()) {
+                input = path.split(['?', '#']).next().unwrap_or_default();
+            }
+        }
+        let input = input.trim_start_matches('/');
+
+        let mut normalized = String::with_capacity(input.len());
+        let mut separator = false;
+        for c in input.chars() {
+            if is_separator(c) {
+                separator = true;
+                continue;
+            }
+            if separator && !normalized.is_empty() {
+                normalized.push('.');
+            }
+            separator = false;
+            normalized.push(c);
+        }
+        if separator && !normalized.is_empty() {
+            normalized.push('.');
+        }
+        normalized
+    }
+
+    /// Return the address as a string slice in the form `"word.word.word"`
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Return the three words of the address
+    pub fn words(&self) -> [&str; 3] {
+        let mut words = self.0.split('.');
+        [
+            words.next().unwrap_or_default(),
+            words.next().unwrap_or_default(),
+            words.next().unwrap_or_default(),
+        ]
+    }
+}
+
+/// Check whether a single word of an address only contains letters, by the same rule as
+/// `text::is_possible_3wa`.
+fn is_valid_word(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(text::is_word_character)
+}
+
+impl FromStr for ThreeWordAddress {
+    type Err = W3WError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = ThreeWordAddress::normalize(s);
+        let words: Vec<&str> = normalized.split('.').collect();
+        if words.len() != 3 || !words.iter().all(|word| is_valid_word(word)) {
+            return Err(W3WError::Validation(format!(
+                "'{}' is not a three word address of the form 'word.word.word'",
+                s
+            )));
+        }
+        Ok(ThreeWordAddress(normalized.to_lowercase()))
+    }
+}
+
+impl TryFrom<&str> for ThreeWordAddress {
+    type Error = W3WError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for ThreeWordAddress {
+    /// Format the address in the form `"word.word.word"`
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for ThreeWordAddress {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for ThreeWordAddress {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
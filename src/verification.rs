@@ -0,0 +1,32 @@
+//! Types of the report produced by `W3WClient::verify_addresses`, which re-resolves stored three
+//! word addresses and flags the ones which no longer match their stored coordinates.
+
+use crate::coordinate::Coordinate;
+use crate::error::W3WError;
+
+/// A stored record whose three word address did not verify.
+#[derive(Debug)]
+pub struct Discrepancy<I> {
+    /// The identifier of the record, as passed to `verify_addresses`
+    pub id: I,
+    /// The three word address of the record
+    pub words: String,
+    /// What is wrong with the record
+    pub kind: DiscrepancyKind,
+}
+
+/// The reason why a stored record did not verify.
+#[derive(Debug)]
+pub enum DiscrepancyKind {
+    /// The address resolved to a coordinate further than the tolerance from the stored one
+    Drifted {
+        /// The stored coordinate
+        expected: Coordinate,
+        /// The coordinate the address resolves to now
+        actual: Coordinate,
+        /// The distance in meters between both coordinates
+        distance_m: f64,
+    },
+    /// The address could not be resolved anymore
+    Failed(W3WError),
+}
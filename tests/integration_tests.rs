@@ -1,4 +1,8 @@
-use what3words::{BoundingBox, Circle, Coordinate, Polygon};
+use what3words::{
+    merge_suggestions, Address, AddressChange, BoundingBox, Circle, Coordinate, CoordinateWarning,
+    CountryCode, GridSection, Orientation, Polygon, Square, Suggestion, SuggestionsExt,
+    ThreeWordAddress, W3WError,
+};
 
 const COORDINATE1: Coordinate = Coordinate {
     latitude: 50.12345,
@@ -22,11 +26,187 @@ fn test_coordinates_to_string() {
     assert_eq!(COORDINATE1.to_string(), String::from("50.12345,-3.98765"));
 }
 
+#[test]
+fn test_coordinate_from_tuple_and_array() {
+    assert_eq!(Coordinate::from((50.12345, -3.98765)), COORDINATE1);
+    assert_eq!(Coordinate::from([50.12345, -3.98765]), COORDINATE1);
+    let coordinate: Coordinate = (&COORDINATE2).into();
+    assert_eq!(coordinate, COORDINATE2);
+}
+
+#[test]
+fn test_shapes_from_tuples() {
+    let bounding_box = BoundingBox::new((51.0, 4.0), [52.0, 5.0]).unwrap();
+    assert_eq!(bounding_box.north_east, Coordinate::from((52.0, 5.0)));
+    assert_eq!(
+        Circle::from_meters((51.0, 4.0), 500.0).centerpoint,
+        Coordinate::from((51.0, 4.0))
+    );
+    let polygon = Polygon::new([(51.0, 4.0), (51.0, 5.0), (52.0, 4.5)]).unwrap();
+    assert_eq!(polygon.coordinates[2], Coordinate::from((52.0, 4.5)));
+}
+
+#[test]
+fn test_coordinate_from_str() {
+    assert_eq!(
+        " 50.12345 , -3.98765 ".parse::<Coordinate>().unwrap(),
+        COORDINATE1
+    );
+    assert_eq!(
+        Coordinate::try_from(COORDINATE1.to_string()).unwrap(),
+        COORDINATE1
+    );
+    for (input, reason) in [
+        ("50.12345", "expected a comma"),
+        ("50.1,-3.9,2", "expected a single comma"),
+        ("north,-3.9", "the latitude 'north' is not a number"),
+        ("50.1,", "the longitude '' is not a number"),
+    ] {
+        match Coordinate::try_from(input) {
+            Err(W3WError::Validation(message)) => assert!(message.ends_with(reason), "{}", message),
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_coordinate_distance() {
+    let london = Coordinate {
+        latitude: 51.5074,
+        longitude: -0.1278,
+    };
+    let paris = Coordinate {
+        latitude: 48.8566,
+        longitude: 2.3522,
+    };
+    assert!((london.distance_to(&paris) - 343_560.0).abs() < 100.0);
+    assert_eq!(london.distance_to(&paris), paris.distance_to(&london));
+    assert_eq!(london.distance_to(&london), 0.0);
+}
+
+#[test]
+fn test_coordinate_bearing_midpoint_and_destination() {
+    let coordinate = |latitude, longitude| Coordinate {
+        latitude,
+        longitude,
+    };
+    let origin = coordinate(51.0, 4.0);
+    assert!((origin.bearing_to(&coordinate(52.0, 4.0)) - 0.0).abs() < 1e-9);
+    assert!((origin.bearing_to(&coordinate(50.0, 4.0)) - 180.0).abs() < 1e-9);
+    assert!((origin.bearing_to(&coordinate(51.0, 3.0)) - 270.4).abs() < 0.1);
+
+    let midpoint = coordinate(0.0, 10.0).midpoint(&coordinate(0.0, 20.0));
+    assert!((midpoint.latitude - 0.0).abs() < 1e-9);
+    assert!((midpoint.longitude - 15.0).abs() < 1e-9);
+    let across = coordinate(0.0, 179.0).midpoint(&coordinate(0.0, -179.0));
+    assert!((across.longitude.abs() - 180.0).abs() < 1e-9);
+
+    let destination = origin.destination(90.0, 1000.0);
+    assert!((origin.distance_to(&destination) - 1000.0).abs() < 1e-6);
+    assert!((origin.bearing_to(&destination) - 90.0).abs() < 0.01);
+    let wrapped = coordinate(0.0, 179.99).destination(90.0, 2000.0);
+    assert!(wrapped.longitude < -179.0);
+}
+
+#[test]
+fn test_coordinate_new() {
+    assert_eq!(Coordinate::new(50.12345, -3.98765).unwrap(), COORDINATE1);
+    assert_eq!(
+        Coordinate::new(0.0, 0.0).unwrap(),
+        Coordinate {
+            latitude: 0.0,
+            longitude: 0.0
+        }
+    );
+    for (latitude, longitude) in [(123.0, 500.0), (150.0, -3.98765), (f64::NAN, 4.0)] {
+        assert!(matches!(
+            Coordinate::new(latitude, longitude),
+            Err(W3WError::Validation(_))
+        ));
+    }
+}
+
+#[test]
+fn test_coordinate_rounded() {
+    let coordinate = Coordinate {
+        latitude: 51.52084712,
+        longitude: -0.19552149,
+    };
+    assert_eq!(coordinate.rounded(6).to_string(), "51.520847,-0.195521");
+    assert_eq!(coordinate.rounded(2).to_string(), "51.52,-0.2");
+    assert_eq!(coordinate.rounded(0).to_string(), "52,-0");
+}
+
+#[test]
+fn test_coordinate_jittered_and_snapped() {
+    let jittered: Vec<Coordinate> = (0..100).map(|_| COORDINATE1.jittered(100.0)).collect();
+    assert!(jittered
+        .iter()
+        .all(|coordinate| coordinate.distance_to(&COORDINATE1) <= 100.0 + 1e-6));
+    assert!(jittered.iter().any(|coordinate| *coordinate != jittered[0]));
+
+    let mut values = [1.0, 0.25].into_iter();
+    let east = COORDINATE1.jittered_with(100.0, || values.next().unwrap());
+    assert!((east.distance_to(&COORDINATE1) - 100.0).abs() < 1e-6);
+    assert!((COORDINATE1.bearing_to(&east) - 90.0).abs() < 0.01);
+
+    let snapped = COORDINATE1.snapped(50.0);
+    assert!(snapped.distance_to(&COORDINATE1) < 50.0);
+    assert_eq!(COORDINATE1.destination(45.0, 5.0).snapped(50.0), snapped);
+}
+
+#[test]
+fn test_coordinate_normalized() {
+    let coordinate = |latitude, longitude| Coordinate {
+        latitude,
+        longitude,
+    };
+    assert_eq!(
+        coordinate(51.5, 364.0).normalized().unwrap(),
+        coordinate(51.5, 4.0)
+    );
+    assert_eq!(
+        coordinate(51.5, -540.0).normalized().unwrap(),
+        coordinate(51.5, -180.0)
+    );
+    assert_eq!(
+        coordinate(-33.8, 180.0).normalized().unwrap(),
+        coordinate(-33.8, 180.0)
+    );
+    for invalid in [coordinate(91.0, 4.0), coordinate(51.5, f64::NAN)] {
+        assert!(matches!(invalid.normalized(), Err(W3WError::Validation(_))));
+    }
+}
+
+#[test]
+fn test_coordinate_warnings() {
+    let coordinate = |latitude, longitude| Coordinate {
+        latitude,
+        longitude,
+    };
+    assert_eq!(COORDINATE1.warning(), None);
+    assert_eq!(
+        coordinate(f64::INFINITY, 4.0).warning(),
+        Some(CoordinateWarning::NotFinite)
+    );
+    assert_eq!(
+        coordinate(151.2, -33.8).warning(),
+        Some(CoordinateWarning::Swapped)
+    );
+    assert_eq!(
+        coordinate(95.0, 190.0).warning(),
+        Some(CoordinateWarning::OutOfRange)
+    );
+    let null_island = coordinate(0.0, 0.0).warning().unwrap();
+    assert_eq!(null_island, CoordinateWarning::NullIsland);
+    assert!(!null_island.is_error());
+}
+
 #[test]
 fn test_bounding_box_to_string() {
     let bounding_box = BoundingBox {
-        south_west: &COORDINATE1,
-        north_east: &COORDINATE2,
+        south_west: COORDINATE1,
+        north_east: COORDINATE2,
     };
     assert_eq!(
         bounding_box.to_string(),
@@ -43,18 +223,48 @@ fn test_bounding_box_to_string() {
 #[test]
 fn test_circle_to_string() {
     let circle = Circle {
-        centerpoint: &COORDINATE1,
+        centerpoint: COORDINATE1,
         radius: 12.3,
     };
     assert_eq!(
         circle.to_string(),
-        format!("{},{}", circle.centerpoint.to_string(), circle.radius)
+        format!("{},{}", circle.centerpoint, circle.radius)
     );
 }
 
+#[test]
+fn test_circle_radius_units() {
+    let circle = Circle::from_meters(COORDINATE1, 2500.0);
+    assert_eq!(circle.radius, 2.5);
+    assert_eq!(circle.to_string(), format!("{},2.5", COORDINATE1));
+    assert_eq!(circle, Circle::from_kilometers(COORDINATE1, 2.5));
+    assert_eq!(circle.radius_m(), 2500.0);
+
+    let circle = Circle::from_miles(COORDINATE1, 10.0);
+    assert!((circle.radius - 16.09344).abs() < 1e-9);
+    assert!((circle.radius_miles() - 10.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_circle_to_polygon() {
+    let circle = Circle::from_meters(COORDINATE2, 1000.0);
+    let polygon = circle.to_polygon(24);
+    assert_eq!(polygon.coordinates.len(), 24);
+    assert_eq!(polygon.normalized(), polygon);
+    assert!(Polygon::new(polygon.coordinates.clone()).is_ok());
+    for coordinate in &polygon.coordinates {
+        assert!((coordinate.distance_to(&COORDINATE2) - 1000.0).abs() < 0.01);
+    }
+    assert!(polygon.coordinates[0].latitude > COORDINATE2.latitude);
+    assert!(polygon.contains(&COORDINATE2));
+
+    assert_eq!(circle.to_polygon(100).coordinates.len(), 24);
+    assert_eq!(circle.to_polygon(0).coordinates.len(), 3);
+}
+
 #[test]
 fn test_polygon_to_string() {
-    let polygon1_list = vec![&COORDINATE1, &COORDINATE2, &COORDINATE3];
+    let polygon1_list = vec![COORDINATE1, COORDINATE2, COORDINATE3];
     let polygon1 = Polygon {
         coordinates: polygon1_list,
     };
@@ -76,14 +286,11 @@ fn test_polygon_to_string() {
         polygon1.to_string(),
         format!(
             "{},{},{},{}",
-            COORDINATE1.to_string(),
-            COORDINATE2.to_string(),
-            COORDINATE3.to_string(),
-            COORDINATE1.to_string()
+            COORDINATE1, COORDINATE2, COORDINATE3, COORDINATE1
         )
     );
 
-    let polygon2_list = vec![&COORDINATE4, &COORDINATE3, &COORDINATE2, &COORDINATE1];
+    let polygon2_list = vec![COORDINATE4, COORDINATE3, COORDINATE2, COORDINATE1];
     let polygon2 = Polygon {
         coordinates: polygon2_list,
     };
@@ -107,11 +314,708 @@ fn test_polygon_to_string() {
         polygon2.to_string(),
         format!(
             "{},{},{},{},{}",
-            COORDINATE4.to_string(),
-            COORDINATE3.to_string(),
-            COORDINATE2.to_string(),
-            COORDINATE1.to_string(),
-            COORDINATE4.to_string()
+            COORDINATE4, COORDINATE3, COORDINATE2, COORDINATE1, COORDINATE4
         )
     );
+
+    let empty = Polygon {
+        coordinates: Vec::new(),
+    };
+    assert_eq!(empty.to_string(), "");
+}
+
+#[test]
+fn test_polygon_owns_coordinates() {
+    let polygon = Polygon {
+        coordinates: [(50.0, 4.0), (51.0, 4.5), (50.5, 5.0)]
+            .iter()
+            .map(|&(latitude, longitude)| Coordinate {
+                latitude,
+                longitude,
+            })
+            .collect(),
+    };
+    let copy = polygon.clone();
+    assert_eq!(polygon, copy);
+    assert_eq!(copy.to_string(), "50,4,51,4.5,50.5,5,50,4");
+}
+
+#[test]
+fn test_polygon_new() {
+    let closed = Polygon::new(vec![COORDINATE1, COORDINATE2, COORDINATE3, COORDINATE1]).unwrap();
+    assert_eq!(closed.coordinates, [COORDINATE1, COORDINATE2, COORDINATE3]);
+    let raw = Polygon {
+        coordinates: vec![COORDINATE1, COORDINATE2, COORDINATE3, COORDINATE1],
+    };
+    assert_eq!(raw.to_string(), closed.to_string());
+
+    let ring: Vec<Coordinate> = (0..24)
+        .map(|i| Coordinate {
+            latitude: 50.0 + f64::from(i).to_radians().sin(),
+            longitude: 4.0 + f64::from(i).to_radians().cos(),
+        })
+        .collect();
+    assert!(Polygon::new(ring.clone()).is_ok());
+    let too_many = [ring.as_slice(), &[COORDINATE4]].concat();
+    let too_few = vec![COORDINATE1, COORDINATE2, COORDINATE1];
+    let invalid = vec![COORDINATE1, COORDINATE2, Coordinate::from((123.0, 500.0))];
+    for coordinates in [too_many, too_few, invalid] {
+        assert!(matches!(
+            Polygon::new(coordinates),
+            Err(W3WError::Validation(_))
+        ));
+    }
+}
+
+#[test]
+fn test_polygon_normalized() {
+    let coordinate = |latitude, longitude| Coordinate {
+        latitude,
+        longitude,
+    };
+    let counter_clockwise = Polygon {
+        coordinates: vec![
+            coordinate(51.0, 4.0),
+            coordinate(51.0, 5.0),
+            coordinate(52.0, 5.0),
+            coordinate(52.0, 4.0),
+        ],
+    };
+    assert_eq!(counter_clockwise.normalized(), counter_clockwise);
+
+    let clockwise = Polygon {
+        coordinates: vec![
+            coordinate(51.0, 4.0),
+            coordinate(52.0, 4.0),
+            coordinate(52.0, 4.0),
+            coordinate(52.0, 5.0),
+            coordinate(51.0, 5.0),
+            coordinate(51.0, 4.0),
+        ],
+    };
+    assert_eq!(clockwise.normalized(), counter_clockwise);
+}
+
+#[test]
+fn test_polygon_centroid_and_area() {
+    let coordinate = |latitude, longitude| Coordinate {
+        latitude,
+        longitude,
+    };
+    let bounding_box = BoundingBox {
+        south_west: coordinate(51.5, -0.2),
+        north_east: coordinate(51.51, -0.18),
+    };
+    let rectangle = bounding_box.to_polygon();
+    let centroid = rectangle.centroid().unwrap();
+    assert!((centroid.latitude - 51.505).abs() < 1e-9);
+    assert!((centroid.longitude + 0.19).abs() < 1e-9);
+    let area_m2 = rectangle.area_m2();
+    assert!((area_m2 - bounding_box.area_m2()).abs() / area_m2 < 0.001);
+
+    let triangle = Polygon {
+        coordinates: vec![
+            coordinate(0.0, 0.0),
+            coordinate(0.0, 3.0),
+            coordinate(3.0, 0.0),
+        ],
+    };
+    let centroid = triangle.centroid().unwrap();
+    assert!((centroid.latitude - 1.0).abs() < 1e-9 && (centroid.longitude - 1.0).abs() < 1e-9);
+
+    let line = Polygon {
+        coordinates: vec![coordinate(51.0, 4.0), coordinate(52.0, 5.0)],
+    };
+    assert_eq!(line.centroid(), Some(coordinate(51.5, 4.5)));
+    assert_eq!(line.area_m2(), 0.0);
+    assert_eq!(
+        Polygon {
+            coordinates: Vec::new()
+        }
+        .centroid(),
+        None
+    );
+}
+
+#[test]
+fn test_polygon_simplified() {
+    let coordinate = |latitude, longitude| Coordinate {
+        latitude,
+        longitude,
+    };
+    // a square traced with 10 points per side, with a slight bump on the northern side
+    let mut coordinates = Vec::new();
+    for i in 0..10 {
+        let step = f64::from(i) / 10.0;
+        coordinates.push(coordinate(51.0, 4.0 + step));
+        coordinates.push(coordinate(51.0 + step, 5.0));
+        coordinates.push(coordinate(52.0 + 0.001 * step, 5.0 - step));
+        coordinates.push(coordinate(52.0 - step, 4.0));
+    }
+    coordinates.sort_by(|a, b| {
+        let angle = |c: &Coordinate| (c.latitude - 51.5).atan2(c.longitude - 4.5);
+        angle(a).total_cmp(&angle(b))
+    });
+    let traced = Polygon { coordinates };
+
+    let simplified = traced.simplified(4);
+    assert_eq!(simplified.coordinates.len(), 4);
+    for corner in [(51.0, 4.0), (51.0, 5.0), (52.0, 5.0), (52.0, 4.0)] {
+        assert!(simplified
+            .coordinates
+            .contains(&coordinate(corner.0, corner.1)));
+    }
+    assert_eq!(traced.simplified(24).coordinates.len(), 24);
+    assert_eq!(traced.simplified(1).coordinates.len(), 3);
+    assert_eq!(traced.simplified(100), traced);
+}
+
+#[test]
+fn test_grid_line_length_and_orientation() {
+    let grid: GridSection = serde_json::from_str(
+        r#"{"lines": [
+            {"start": {"lng": 4.0, "lat": 51.0}, "end": {"lng": 4.001, "lat": 51.0}},
+            {"start": {"lng": 4.0, "lat": 51.0}, "end": {"lng": 4.0, "lat": 51.001}}
+        ]}"#,
+    )
+    .unwrap();
+    let horizontal = grid.lines[0];
+    let vertical = grid.lines[1];
+    assert_eq!(horizontal.orientation(), Orientation::Horizontal);
+    assert_eq!(vertical.orientation(), Orientation::Vertical);
+    assert!((horizontal.length_m() - 69.98).abs() < 0.1);
+    assert!((vertical.length_m() - 111.19).abs() < 0.1);
+}
+
+#[test]
+fn test_square_size() {
+    let square = Square {
+        south_west: Coordinate {
+            latitude: 51.520833,
+            longitude: -0.195543,
+        },
+        north_east: Coordinate {
+            latitude: 51.52086,
+            longitude: -0.195499,
+        },
+    };
+    let (width, height) = square.approx_size_m();
+    assert!((width - 3.0).abs() < 0.1);
+    assert!((height - 3.0).abs() < 0.1);
+    assert!((square.approx_area_m2() - 9.0).abs() < 0.5);
+    assert!((square.center().latitude - 51.5208465).abs() < 1e-9);
+}
+
+#[test]
+fn test_three_word_address_parsing() {
+    let address: ThreeWordAddress = "Filled.COUNT.soap".parse().unwrap();
+    assert_eq!(address.to_string(), "filled.count.soap");
+    assert_eq!(address.words(), ["filled", "count", "soap"]);
+    assert!(ThreeWordAddress::try_from("índice.пример.mañana").is_ok());
+
+    for invalid in [
+        "filled.count",
+        "filled.count.soap.x",
+        "filled..soap",
+        "filled.c0unt.soap",
+        "filled.count.so,ap",
+    ] {
+        assert!(matches!(
+            invalid.parse::<ThreeWordAddress>(),
+            Err(W3WError::Validation(_))
+        ));
+    }
+}
+
+#[test]
+fn test_three_word_address_normalization() {
+    for input in [
+        "filled.count.soap",
+        "///filled.count.soap",
+        "https://w3w.co/filled.count.soap",
+        "http://what3words.com/filled.count.soap?maptype=satellite",
+        "  filled count  soap ",
+        "filled. count. soap",
+    ] {
+        assert_eq!(ThreeWordAddress::normalize(input), "filled.count.soap");
+    }
+    assert_eq!(
+        ThreeWordAddress::normalize("filled count."),
+        "filled.count."
+    );
+    assert_eq!(
+        "///Filled.Count.Soap".parse::<ThreeWordAddress>().unwrap(),
+        "filled.count.soap".parse::<ThreeWordAddress>().unwrap()
+    );
+}
+
+#[test]
+fn test_three_word_address_separators_of_other_scripts() {
+    let address: ThreeWordAddress = "///こくさい・ていか・かざす".parse().unwrap();
+    assert_eq!(address.as_str(), "こくさい.ていか.かざす");
+    assert_eq!(address.words(), ["こくさい", "ていか", "かざす"]);
+    assert_eq!(
+        ThreeWordAddress::normalize("こくさい\u{3000}ていか。かざす"),
+        "こくさい.ていか.かざす"
+    );
+    assert_eq!(ThreeWordAddress::normalize("नाम।पता।घर"), "नाम.पता.घर");
+}
+
+const ADDRESS_JSON: &str = r#"{
+    "country": "GB",
+    "square": {
+        "southwest": {"lng": -0.195543, "lat": 51.520833},
+        "northeast": {"lng": -0.195499, "lat": 51.52086}
+    },
+    "nearestPlace": "Bayswater, London",
+    "coordinates": {"lng": -0.195521, "lat": 51.520847},
+    "words": "filled.count.soap",
+    "language": "en",
+    "map": "https://w3w.co/filled.count.soap"
+}"#;
+
+#[test]
+fn test_address_diff() {
+    let stored: Address = serde_json::from_str(ADDRESS_JSON).unwrap();
+    assert_eq!(stored.words, "filled.count.soap");
+    assert_eq!(stored.square.south_west.latitude, 51.520833);
+    assert_eq!(stored.locale, None);
+    assert_eq!(stored.country.to_string(), "GB");
+    assert_eq!(stored.country.name(), Some("Britain (UK)"));
+    assert!(stored.diff(&stored.clone()).is_empty());
+
+    let mut current = stored.clone();
+    current.nearest_place = String::from("Paddington, London");
+    current.words = String::from("index.home.raft");
+    assert_eq!(
+        stored.diff(&current),
+        vec![
+            AddressChange::Words {
+                old: String::from("filled.count.soap"),
+                new: String::from("index.home.raft"),
+            },
+            AddressChange::NearestPlace {
+                old: String::from("Bayswater, London"),
+                new: String::from("Paddington, London"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_country_code() {
+    let code: CountryCode = "be".parse().unwrap();
+    assert_eq!(code.as_str(), "BE");
+    assert_eq!(code.name(), Some("Belgium"));
+    assert_eq!(CountryCode::try_from("ZZ").unwrap().name(), None);
+    for invalid in ["B", "BEL", "B1", "", "ß"] {
+        assert!(matches!(
+            invalid.parse::<CountryCode>(),
+            Err(W3WError::Validation(_))
+        ));
+    }
+    assert!(serde_json::from_str::<CountryCode>(r#""GBR""#).is_err());
+}
+
+#[test]
+fn test_suggestion_with_coordinates_from_json() {
+    let suggestion: Suggestion = serde_json::from_str(
+        r#"{
+            "country": "GB",
+            "nearestPlace": "Bayswater, London",
+            "words": "filled.count.soap",
+            "rank": 1,
+            "language": "en",
+            "square": {
+                "southwest": {"lng": -0.195543, "lat": 51.520833},
+                "northeast": {"lng": -0.195499, "lat": 51.52086}
+            },
+            "coordinates": {"lng": -0.195521, "lat": 51.520847},
+            "map": "https://w3w.co/filled.count.soap"
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(suggestion.rank, 1);
+    assert_eq!(suggestion.distance_to_focus_km, None);
+    assert_eq!(
+        suggestion.coordinates,
+        Some(Coordinate {
+            latitude: 51.520847,
+            longitude: -0.195521,
+        })
+    );
+
+    let suggestion: Suggestion = serde_json::from_str(
+        r#"{"country": "GB", "nearestPlace": "Bayswater, London", "words": "filled.count.soap",
+            "rank": 1, "language": "en", "distanceToFocusKm": 4}"#,
+    )
+    .unwrap();
+    assert_eq!(suggestion.distance_to_focus_km, Some(4.0));
+    assert_eq!(suggestion.square, None);
+}
+
+#[test]
+fn test_suggestion_sorting_and_filtering() {
+    let suggestion = |words: &str, country: &str, distance: Option<f64>| Suggestion {
+        country: country.parse().unwrap(),
+        nearest_place: String::from("Somewhere"),
+        words: words.to_string(),
+        rank: 1,
+        language: String::from("en"),
+        distance_to_focus_km: distance,
+        square: None,
+        coordinates: None,
+        map: None,
+    };
+    let suggestions = vec![
+        suggestion("far.away.place", "BE", Some(12.0)),
+        suggestion("no.focus.here", "BE", None),
+        suggestion("out.at.sea", "ZZ", Some(1.0)),
+        suggestion("close.to.home", "BE", Some(2.0)),
+        suggestion("across.the.border", "NL", Some(3.0)),
+    ];
+    let words = |suggestions: Vec<Suggestion>| -> Vec<String> {
+        suggestions.into_iter().map(|s| s.words).collect()
+    };
+
+    assert_eq!(
+        words(suggestions.clone().without_sea().sort_by_distance()),
+        [
+            "close.to.home",
+            "across.the.border",
+            "far.away.place",
+            "no.focus.here"
+        ]
+    );
+    assert_eq!(
+        words(suggestions.filter_country("be").sort_by_distance().top(2)),
+        ["close.to.home", "far.away.place"]
+    );
+}
+
+#[test]
+fn test_merge_suggestions() {
+    let suggestion = |words: &str, rank: u32, distance: Option<f64>| Suggestion {
+        country: "GB".parse().unwrap(),
+        nearest_place: String::from("Bayswater, London"),
+        words: words.to_string(),
+        rank,
+        language: String::from("en"),
+        distance_to_focus_km: distance,
+        square: None,
+        coordinates: None,
+        map: None,
+    };
+    let merged = merge_suggestions([
+        vec![
+            suggestion("filled.count.soap", 1, Some(3.0)),
+            suggestion("filled.count.soaps", 2, None),
+            suggestion("filled.count.sand", 3, Some(9.0)),
+        ],
+        vec![
+            suggestion("filled.count.sand", 1, Some(8.0)),
+            suggestion("filled.count.soap", 2, Some(2.0)),
+        ],
+    ]);
+    let words: Vec<(&str, u32)> = merged.iter().map(|s| (s.words.as_str(), s.rank)).collect();
+    assert_eq!(
+        words,
+        [
+            ("filled.count.soap", 1),
+            ("filled.count.sand", 1),
+            ("filled.count.soaps", 2)
+        ]
+    );
+    assert_eq!(merged[0].distance_to_focus_km, Some(2.0));
+    assert_eq!(merged[1].distance_to_focus_km, Some(8.0));
+}
+
+#[test]
+fn test_bounding_box_size() {
+    let bounding_box = BoundingBox {
+        south_west: Coordinate {
+            latitude: 51.5,
+            longitude: -0.2,
+        },
+        north_east: Coordinate {
+            latitude: 51.52,
+            longitude: -0.17,
+        },
+    };
+    let (width, height) = bounding_box.approx_size_m();
+    assert!((width - 2077.0).abs() < 1.0, "{}", width);
+    assert!((height - 2224.0).abs() < 1.0, "{}", height);
+    assert!((bounding_box.diagonal_m() - 3043.0).abs() < 1.0);
+    assert_eq!(bounding_box.width_m(), width);
+    assert_eq!(bounding_box.height_m(), height);
+    assert!((bounding_box.area_m2() - width * height).abs() < 1e-6);
+
+    let wrapped = BoundingBox {
+        south_west: Coordinate {
+            latitude: 0.0,
+            longitude: 179.99,
+        },
+        north_east: Coordinate {
+            latitude: 0.01,
+            longitude: -179.99,
+        },
+    };
+    let beyond_180 = BoundingBox {
+        north_east: Coordinate {
+            latitude: 0.01,
+            longitude: 180.01,
+        },
+        ..wrapped.clone()
+    };
+    assert!(
+        (wrapped.width_m() - 2224.0).abs() < 1.0,
+        "{}",
+        wrapped.width_m()
+    );
+    assert!((wrapped.width_m() - beyond_180.width_m()).abs() < 1e-6);
+    assert!((wrapped.area_m2() - beyond_180.area_m2()).abs() < 1e-3);
+}
+
+#[test]
+fn test_bounding_box_new() {
+    let coordinate = |latitude, longitude| Coordinate {
+        latitude,
+        longitude,
+    };
+    let fiji = BoundingBox::new(coordinate(-21.0, 177.0), coordinate(-12.0, -178.0)).unwrap();
+    assert_eq!(fiji.south_west, coordinate(-21.0, 177.0));
+    assert_eq!(fiji.north_east, coordinate(-12.0, 182.0));
+    assert!(fiji.contains(&coordinate(-17.0, 179.0)));
+    assert!(fiji.contains(&coordinate(-17.0, -179.0)));
+    assert!(!fiji.contains(&coordinate(-17.0, 170.0)));
+
+    let documented = BoundingBox::new(coordinate(-4.0, 178.2), coordinate(22.0, 195.4)).unwrap();
+    assert_eq!(documented.north_east.longitude, 195.4);
+    let wrapped = BoundingBox::new(coordinate(51.0, 364.0), coordinate(52.0, 365.0)).unwrap();
+    assert_eq!(wrapped.south_west.longitude, 4.0);
+    assert_eq!(wrapped.north_east.longitude, 5.0);
+    let world = BoundingBox::new(coordinate(-90.0, -180.0), coordinate(90.0, 180.0)).unwrap();
+    assert_eq!(world.north_east.longitude, 180.0);
+
+    for (south_west, north_east) in [
+        (coordinate(52.0, 4.0), coordinate(51.0, 5.0)),
+        (coordinate(51.0, 4.0), coordinate(91.0, 5.0)),
+        (coordinate(f64::NAN, 4.0), coordinate(52.0, 5.0)),
+    ] {
+        assert!(matches!(
+            BoundingBox::new(south_west, north_east),
+            Err(W3WError::Validation(_))
+        ));
+    }
+}
+
+#[test]
+fn test_bounding_box_from_center() {
+    let center = Coordinate {
+        latitude: 60.0,
+        longitude: 10.0,
+    };
+    let bounding_box = BoundingBox::from_center(center, 500.0, 300.0);
+    assert!((bounding_box.width_m() - 500.0).abs() < 1e-6);
+    assert!((bounding_box.height_m() - 300.0).abs() < 1e-6);
+    let d_lng = bounding_box.north_east.longitude - bounding_box.south_west.longitude;
+    let d_lat = bounding_box.north_east.latitude - bounding_box.south_west.latitude;
+    assert!((d_lng / d_lat - 500.0 / 300.0 * 2.0).abs() < 1e-6);
+
+    let around_circle = BoundingBox::from_circle(&Circle {
+        centerpoint: center,
+        radius: 1.0,
+    });
+    assert!((around_circle.width_m() - 2000.0).abs() < 1e-6);
+    assert!(around_circle.contains(&center.destination(0.0, 999.0)));
+    assert!(!around_circle.contains(&center.destination(0.0, 1001.0)));
+}
+
+#[test]
+fn test_bounding_box_expand_and_intersection() {
+    let coordinate = |latitude, longitude| Coordinate {
+        latitude,
+        longitude,
+    };
+    let bounding_box = BoundingBox {
+        south_west: coordinate(51.5, -0.2),
+        north_east: coordinate(51.52, -0.17),
+    };
+    let expanded = bounding_box.expand_meters(100.0);
+    assert!((expanded.height_m() - bounding_box.height_m() - 200.0).abs() < 0.01);
+    assert!((expanded.width_m() - bounding_box.width_m() - 200.0).abs() < 0.01);
+    assert_eq!(expanded.expand_meters(-100.0).south_west.latitude, 51.5);
+
+    let collapsed = bounding_box.expand_meters(-1500.0);
+    assert_eq!(collapsed.south_west, collapsed.north_east);
+    assert!((collapsed.south_west.latitude - 51.51).abs() < 1e-9);
+    assert!((collapsed.south_west.longitude + 0.185).abs() < 1e-9);
+    let narrowed = bounding_box.expand_meters(-1050.0);
+    assert!(narrowed.south_west.latitude < narrowed.north_east.latitude);
+    assert_eq!(narrowed.south_west.longitude, narrowed.north_east.longitude);
+
+    let wrapped = BoundingBox {
+        south_west: coordinate(0.0, 179.99),
+        north_east: coordinate(0.01, -179.99),
+    };
+    let expanded = wrapped.expand_meters(100.0);
+    assert!((expanded.width_m() - wrapped.width_m() - 200.0).abs() < 0.01);
+    assert!(expanded.north_east.longitude > 180.0);
+
+    let other = BoundingBox {
+        south_west: coordinate(51.51, -0.18),
+        north_east: coordinate(51.6, -0.1),
+    };
+    assert_eq!(
+        bounding_box.intersection(&other),
+        Some(BoundingBox {
+            south_west: coordinate(51.51, -0.18),
+            north_east: coordinate(51.52, -0.17),
+        })
+    );
+    let apart = BoundingBox {
+        south_west: coordinate(52.0, -0.2),
+        north_east: coordinate(52.1, -0.1),
+    };
+    assert_eq!(bounding_box.intersection(&apart), None);
+}
+
+#[test]
+fn test_grid_section_to_geojson() {
+    let grid: GridSection = serde_json::from_str(
+        r#"{"lines": [{"start": {"lat": 51.52, "lng": -0.196}, "end": {"lat": 51.52, "lng": -0.195}}]}"#,
+    )
+    .unwrap();
+    assert_eq!(
+        grid.to_geojson(),
+        serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "geometry": {
+                    "type": "MultiLineString",
+                    "coordinates": [[[-0.196, 51.52], [-0.195, 51.52]]]
+                },
+                "properties": {}
+            }]
+        })
+    );
+}
+
+#[test]
+fn test_geometries_to_wkt() {
+    let coordinate = |latitude, longitude| Coordinate {
+        latitude,
+        longitude,
+    };
+    assert_eq!(coordinate(51.52, -0.2).to_wkt(), "POINT(-0.2 51.52)");
+
+    let bounding_box = BoundingBox {
+        south_west: coordinate(51.0, 4.0),
+        north_east: coordinate(52.0, 5.5),
+    };
+    assert_eq!(
+        bounding_box.to_wkt(),
+        "POLYGON((4 51, 5.5 51, 5.5 52, 4 52, 4 51))"
+    );
+    let square: Square = serde_json::from_str(
+        r#"{"southwest": {"lat": 51.0, "lng": 4.0}, "northeast": {"lat": 52.0, "lng": 5.5}}"#,
+    )
+    .unwrap();
+    assert_eq!(square.to_wkt(), bounding_box.to_wkt());
+
+    let triangle = Polygon {
+        coordinates: vec![
+            coordinate(51.0, 4.0),
+            coordinate(51.0, 5.0),
+            coordinate(52.0, 4.5),
+        ],
+    };
+    assert_eq!(triangle.to_wkt(), "POLYGON((4 51, 5 51, 4.5 52, 4 51))");
+    assert_eq!(
+        Polygon {
+            coordinates: Vec::new()
+        }
+        .to_wkt(),
+        "POLYGON EMPTY"
+    );
+
+    let wkt = Circle::from_meters(COORDINATE1, 100.0).to_wkt();
+    assert!(wkt.starts_with("POLYGON((-3.98765"), "{}", wkt);
+    assert_eq!(wkt.matches(',').count(), 24);
+}
+
+#[test]
+fn test_square_conversions() {
+    let square: Square = serde_json::from_str(
+        r#"{"southwest": {"lat": 51.520833, "lng": -0.195543}, "northeast": {"lat": 51.52086, "lng": -0.195499}}"#,
+    )
+    .unwrap();
+    let polygon = square.to_polygon();
+    assert_eq!(polygon.coordinates.len(), 4);
+    assert_eq!(polygon.coordinates[0], square.south_west);
+    assert_eq!(
+        polygon.coordinates[1].longitude,
+        square.north_east.longitude
+    );
+    assert_eq!(polygon.coordinates[2], square.north_east);
+    assert_eq!(
+        polygon.coordinates[3].longitude,
+        square.south_west.longitude
+    );
+
+    let bounding_box = square.to_bounding_box();
+    assert_eq!(bounding_box.south_west, square.south_west);
+    assert_eq!(bounding_box.north_east, square.north_east);
+
+    assert!(square.contains(&square.center()));
+    assert!(square.contains(&square.south_west));
+    assert!(!square.contains(&square.north_east));
+    assert!(!square.contains(&Coordinate {
+        latitude: 51.52,
+        longitude: -0.1955,
+    }));
+}
+
+#[test]
+fn test_shapes_contain_coordinates() {
+    let coordinate = |latitude, longitude| Coordinate {
+        latitude,
+        longitude,
+    };
+    let bounding_box = BoundingBox {
+        south_west: coordinate(51.0, 4.0),
+        north_east: coordinate(52.0, 5.0),
+    };
+    assert!(bounding_box.contains(&coordinate(51.5, 4.5)));
+    assert!(bounding_box.contains(&coordinate(51.0, 5.0)));
+    assert!(!bounding_box.contains(&coordinate(52.5, 4.5)));
+    let across_antimeridian = BoundingBox {
+        south_west: coordinate(-20.0, 178.0),
+        north_east: coordinate(-15.0, -178.0),
+    };
+    assert!(across_antimeridian.contains(&coordinate(-17.0, 179.5)));
+    assert!(across_antimeridian.contains(&coordinate(-17.0, -179.5)));
+    assert!(!across_antimeridian.contains(&coordinate(-17.0, 0.0)));
+
+    let circle = Circle {
+        centerpoint: coordinate(51.0, 4.0),
+        radius: 10.0,
+    };
+    assert!(circle.contains(&coordinate(51.08, 4.0)));
+    assert!(!circle.contains(&coordinate(51.1, 4.0)));
+
+    let triangle = Polygon {
+        coordinates: vec![
+            coordinate(51.0, 4.0),
+            coordinate(52.0, 4.5),
+            coordinate(51.0, 5.0),
+        ],
+    };
+    assert!(triangle.contains(&coordinate(51.2, 4.5)));
+    assert!(!triangle.contains(&coordinate(51.9, 4.1)));
+    assert!(!Polygon {
+        coordinates: Vec::new()
+    }
+    .contains(&coordinate(51.2, 4.5)));
 }
@@ -0,0 +1,149 @@
+//! Contract tests against the live what3words API. They only run with the `live-tests` feature
+//! and a real key in `W3W_API_KEY`:
+//!
+//! ```text
+//! W3W_API_KEY=<your API key> cargo test --features live-tests --test live_tests
+//! ```
+//!
+//! Every response is reduced to its shape, i.e. its keys and the types of its values, and
+//! compared to the shape of the golden fixture in `tests/fixtures`. A failure means the API
+//! changed its response format. Set `W3W_UPDATE_FIXTURES=1` to store the live responses as the
+//! new fixtures.
+#![cfg(feature = "live-tests")]
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde_json::Value;
+use what3words::{
+    AutoSuggestOptions, BoundingBox, ConvertTo3WAOptions, ConvertToCoordinatesOptions, Coordinate,
+    GridSectionOptions, W3WClient,
+};
+
+const COORDINATE: Coordinate = Coordinate {
+    latitude: 51.520847,
+    longitude: -0.195521,
+};
+
+fn client() -> W3WClient {
+    W3WClient::from_env().expect("the live tests need a real key in W3W_API_KEY")
+}
+
+/// The structure of a JSON value, without the values themselves.
+#[derive(Debug, PartialEq)]
+enum Shape {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array(Box<Shape>),
+    Object(BTreeMap<String, Shape>),
+}
+
+/// Reduce a value to its shape. The shape of an array is the merged shape of its elements, so
+/// keys which only some elements have are included.
+fn shape(value: &Value) -> Shape {
+    match value {
+        Value::Null => Shape::Null,
+        Value::Bool(_) => Shape::Bool,
+        Value::Number(_) => Shape::Number,
+        Value::String(_) => Shape::String,
+        Value::Array(values) => {
+            Shape::Array(Box::new(values.iter().map(shape).fold(Shape::Null, merge)))
+        }
+        Value::Object(fields) => Shape::Object(
+            fields
+                .iter()
+                .map(|(name, value)| (name.clone(), shape(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Merge two shapes into one which has the keys of both.
+fn merge(left: Shape, right: Shape) -> Shape {
+    match (left, right) {
+        (Shape::Null, shape) | (shape, Shape::Null) => shape,
+        (Shape::Object(mut left), Shape::Object(right)) => {
+            for (name, shape) in right {
+                let merged = match left.remove(&name) {
+                    Some(existing) => merge(existing, shape),
+                    None => shape,
+                };
+                left.insert(name, merged);
+            }
+            Shape::Object(left)
+        }
+        (Shape::Array(left), Shape::Array(right)) => Shape::Array(Box::new(merge(*left, *right))),
+        (left, _) => left,
+    }
+}
+
+/// Compare the shape of `live` to the shape of the fixture `name`, or store `live` as the
+/// fixture when `W3W_UPDATE_FIXTURES` is set.
+fn assert_matches_fixture(name: &str, live: &Value) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(format!("{}.json", name));
+    if std::env::var_os("W3W_UPDATE_FIXTURES").is_some() {
+        let json = serde_json::to_string_pretty(live).unwrap();
+        std::fs::write(&path, json + "\n").unwrap();
+        return;
+    }
+    let fixture: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(
+        shape(live),
+        shape(&fixture),
+        "the response of {} no longer matches {}",
+        name,
+        path.display()
+    );
+}
+
+#[test]
+fn test_convert_to_3wa_contract() {
+    let json = client()
+        .convert_to_3wa_json(COORDINATE, &ConvertTo3WAOptions::default())
+        .unwrap();
+    assert_matches_fixture("convert_to_3wa", &json);
+}
+
+#[test]
+fn test_convert_to_coordinates_contract() {
+    let json = client()
+        .convert_to_coordinates_json("filled.count.soap", &ConvertToCoordinatesOptions::default())
+        .unwrap();
+    assert_matches_fixture("convert_to_coordinates", &json);
+}
+
+#[test]
+fn test_autosuggest_contract() {
+    let json = client()
+        .autosuggest_json("filled.count.so", &AutoSuggestOptions::default())
+        .unwrap();
+    assert_matches_fixture("autosuggest", &json);
+}
+
+#[test]
+fn test_available_languages_contract() {
+    let json = client().available_languages_json().unwrap();
+    assert_matches_fixture("available_languages", &json);
+}
+
+#[test]
+fn test_grid_section_contract() {
+    let bounding_box = BoundingBox {
+        south_west: Coordinate {
+            latitude: 51.520833,
+            longitude: -0.195543,
+        },
+        north_east: Coordinate {
+            latitude: 51.52086,
+            longitude: -0.195499,
+        },
+    };
+    let json = client()
+        .grid_section_json(&bounding_box, &GridSectionOptions::default())
+        .unwrap();
+    assert_matches_fixture("grid_section", &json);
+}